@@ -0,0 +1,82 @@
+//! Thin wrapper around the CPU control registers that make the FPU treat
+//! denormal (subnormal) floats as zero.
+//!
+//! * x86_64: sets FTZ (flush-to-zero, MXCSR bit 15) and DAZ
+//!   (denormals-are-zero, MXCSR bit 6).
+//! * aarch64: sets FZ (FPCR bit 24), which covers both inputs and outputs.
+//! * anything else: unsupported, the calls are no-ops that report `false`.
+//!
+//! The flags are per-thread state. They only affect the thread that sets
+//! them and any threads it spawns afterwards.
+
+#[cfg(target_arch = "x86_64")]
+const MXCSR_DAZ: u32 = 1 << 6;
+#[cfg(target_arch = "x86_64")]
+const MXCSR_FTZ: u32 = 1 << 15;
+#[cfg(target_arch = "aarch64")]
+const FPCR_FZ: u64 = 1 << 24;
+
+/// Human readable name of the mechanism used on this platform.
+pub fn mechanism() -> &'static str {
+    if cfg!(target_arch = "x86_64") {
+        "x86_64 MXCSR FTZ+DAZ"
+    } else if cfg!(target_arch = "aarch64") {
+        "aarch64 FPCR FZ"
+    } else {
+        "unsupported"
+    }
+}
+
+/// Returns true when this platform can flush denormals.
+pub fn is_supported() -> bool {
+    cfg!(any(target_arch = "x86_64", target_arch = "aarch64"))
+}
+
+/// Enables or disables denormal flushing on the current thread.
+/// Returns whether the flags were previously enabled, or `None` when the
+/// platform is unsupported.
+pub fn set_flush_denormals(enabled: bool) -> Option<bool> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let mut csr: u32 = 0;
+        // SAFETY: stmxcsr/ldmxcsr only read and write the SSE control register.
+        unsafe {
+            std::arch::asm!("stmxcsr [{}]", in(reg) &mut csr, options(nostack));
+        }
+        let previous = csr & (MXCSR_DAZ | MXCSR_FTZ) == (MXCSR_DAZ | MXCSR_FTZ);
+        let updated = if enabled {
+            csr | MXCSR_DAZ | MXCSR_FTZ
+        } else {
+            csr & !(MXCSR_DAZ | MXCSR_FTZ)
+        };
+        unsafe {
+            std::arch::asm!("ldmxcsr [{}]", in(reg) &updated, options(nostack, readonly));
+        }
+        Some(previous)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let mut fpcr: u64;
+        // SAFETY: FPCR is the per-thread floating point control register.
+        unsafe {
+            std::arch::asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack));
+        }
+        let previous = fpcr & FPCR_FZ != 0;
+        if enabled {
+            fpcr |= FPCR_FZ;
+        } else {
+            fpcr &= !FPCR_FZ;
+        }
+        unsafe {
+            std::arch::asm!("msr fpcr, {}", in(reg) fpcr, options(nomem, nostack));
+        }
+        Some(previous)
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = enabled;
+        None
+    }
+}
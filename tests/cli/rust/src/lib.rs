@@ -0,0 +1,25 @@
+//! Loads any of the three classifier pipelines behind the shared
+//! `Classifier` trait, for callers that pick the model type at run time.
+
+use anyhow::Result;
+use std::path::Path;
+
+pub use whitelightning_common::{ClassificationResult, Classifier, MODEL_FILE_NAMES};
+
+/// Which harness pipeline a model directory is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClassifierKind {
+    Binary,
+    Multiclass,
+    Sigmoid,
+}
+
+/// Loads the `kind` classifier from the `MODEL_FILE_NAMES` files in `dir`,
+/// with each pipeline's default options.
+pub fn classifier_from_dir<P: AsRef<Path>>(dir: P, kind: ClassifierKind) -> Result<Box<dyn Classifier>> {
+    match kind {
+        ClassifierKind::Binary => binary_classifier::classifier_from_dir(dir),
+        ClassifierKind::Multiclass => multiclass_classifier::classifier_from_dir(dir),
+        ClassifierKind::Sigmoid => multiclass_sigmoid::classifier_from_dir(dir),
+    }
+}
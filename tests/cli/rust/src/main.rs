@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use whitelightning::{classifier_from_dir, ClassificationResult, ClassifierKind};
+use whitelightning_common::{init_logging, LogFormat};
+
+/// One entry point for the WhiteLightning Rust test harnesses. Everything
+/// after the subcommand name is handed to that harness unchanged, so its own
+/// flags and `--help` work as they do in the standalone binaries.
+#[derive(Parser)]
+#[command(name = "whitelightning", version, about = "Run the WhiteLightning ONNX test harnesses")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Binary sentiment classifier (same flags as binary_classifier's test_onnx_model)
+    #[command(disable_help_flag = true)]
+    Binary {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<OsString>,
+    },
+    /// Multiclass topic classifier (same flags as multiclass_classifier's test_onnx_model)
+    #[command(disable_help_flag = true)]
+    Multiclass {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<OsString>,
+    },
+    /// Multi-label sigmoid classifier demo (same flags as multiclass_sigmoid_test)
+    #[command(disable_help_flag = true)]
+    Sigmoid {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Classify TEXT with any of the three pipelines through the shared Classifier interface, printing tab-separated label/probability lines
+    Classify {
+        /// Pipeline the model directory is for
+        #[arg(long, value_enum)]
+        kind: ClassifierKind,
+        /// Directory holding model.onnx, vocab.json and scaler.json
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+        text: String,
+    },
+}
+
+/// Puts `name` in front of `args` as the program name the harness parsers
+/// expect, so usage lines read `whitelightning binary ...`.
+fn with_program_name<T: From<&'static str>>(name: &'static str, args: Vec<T>) -> Vec<T> {
+    std::iter::once(T::from(name)).chain(args).collect()
+}
+
+/// Prints `label<TAB>probability`: the predicted label for a binary result,
+/// one line per label for a label vector.
+fn print_result(result: &ClassificationResult) {
+    match result {
+        ClassificationResult::Binary { label, probability } => println!("{}\t{:.6}", label, probability),
+        ClassificationResult::Labels(probabilities) => {
+            for (label, probability) in probabilities {
+                println!("{}\t{:.6}", label, probability);
+            }
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Binary { args } => binary_classifier::run(with_program_name("whitelightning binary", args)),
+        Command::Multiclass { args } => {
+            multiclass_classifier::run(with_program_name("whitelightning multiclass", args))
+        }
+        Command::Sigmoid { args } => {
+            multiclass_sigmoid::run(with_program_name("whitelightning sigmoid", args)).map_err(|e| anyhow!("{}", e))
+        }
+        Command::Classify { kind, dir, text } => {
+            // The harness subcommands install the subscriber from their own --log-format
+            init_logging(LogFormat::Pretty);
+            print_result(&classifier_from_dir(dir, kind)?.classify(&text)?);
+            Ok(())
+        }
+    }
+}
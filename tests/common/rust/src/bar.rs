@@ -0,0 +1,30 @@
+/// Terminal width assumed when `COLUMNS` is unset or invalid.
+pub const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// Eighth-block characters for the partly filled last cell of a bar, from
+/// one eighth to seven eighths.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Width of the terminal in columns, from the `COLUMNS` environment variable
+/// that shells export, else `DEFAULT_TERMINAL_WIDTH`.
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|&columns: &usize| columns > 0)
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Horizontal bar for `probability` out of `width` cells, drawn with block
+/// characters at eighth-cell resolution. Values outside 0-1 are clamped and
+/// NaN draws an empty bar.
+pub fn probability_bar(probability: f32, width: usize) -> String {
+    let probability = if probability.is_nan() { 0.0 } else { probability.clamp(0.0, 1.0) };
+    let eighths = (probability as f64 * width as f64 * 8.0).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    let partial = eighths % 8;
+    if partial > 0 {
+        bar.push(PARTIAL_BLOCKS[partial - 1]);
+    }
+    bar
+}
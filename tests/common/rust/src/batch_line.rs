@@ -0,0 +1,228 @@
+//! The `--output-fields` / `--output-delimiter` layout of the per-input
+//! result lines of `--input-file` and `--csv` runs, for pipelines that
+//! expect a fixed set of delimited columns instead of the readable default.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Delimiter between `--output-fields` values when `--output-delimiter` is
+/// not given.
+pub const DEFAULT_OUTPUT_DELIMITER: char = '\t';
+
+/// A value of a batch result line, selected with `--output-fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputField {
+    /// 1-based line number of the input in its file.
+    Index,
+    /// The classified text.
+    Text,
+    /// Positive-class probability, or the predicted class's confidence.
+    Probability,
+    /// Predicted label.
+    Label,
+    /// End-to-end latency of the prediction in ms.
+    Latency,
+}
+
+impl OutputField {
+    /// Every field, in the order used when `--output-fields` is not given.
+    pub const ALL: [OutputField; 5] = [
+        OutputField::Index,
+        OutputField::Text,
+        OutputField::Probability,
+        OutputField::Label,
+        OutputField::Latency,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputField::Index => "index",
+            OutputField::Text => "text",
+            OutputField::Probability => "probability",
+            OutputField::Label => "label",
+            OutputField::Latency => "latency",
+        }
+    }
+}
+
+impl FromStr for OutputField {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        OutputField::ALL.into_iter().find(|field| field.name() == value).ok_or_else(|| {
+            format!(
+                "unknown output field '{}' - expected 'index', 'text', 'probability', 'label' or 'latency'",
+                value
+            )
+        })
+    }
+}
+
+impl fmt::Display for OutputField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Comma-separated `--output-fields` list, e.g. `index,label,probability`,
+/// in the order the values are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFields(pub Vec<OutputField>);
+
+impl Default for OutputFields {
+    fn default() -> Self {
+        OutputFields(OutputField::ALL.to_vec())
+    }
+}
+
+impl FromStr for OutputFields {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut fields = Vec::new();
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let field: OutputField = name.parse()?;
+            if fields.contains(&field) {
+                return Err(format!("output field '{}' is listed twice", field));
+            }
+            fields.push(field);
+        }
+        if fields.is_empty() {
+            return Err("expected at least one output field".to_string());
+        }
+        Ok(OutputFields(fields))
+    }
+}
+
+impl fmt::Display for OutputFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.0.iter().map(|field| field.name()).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+/// Parses an `--output-delimiter` value: a single character, or the two
+/// characters `\t` for a tab, which is awkward to pass through a shell.
+pub fn parse_output_delimiter(value: &str) -> Result<char, String> {
+    if value == "\\t" {
+        return Ok('\t');
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some('\n' | '\r'), None) => Err("a line break cannot separate output fields".to_string()),
+        (Some(delimiter), None) => Ok(delimiter),
+        _ => Err(format!("expected a single character or '\\t', got '{}'", value)),
+    }
+}
+
+/// One classified input of a batch run.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLine<'a> {
+    pub index: usize,
+    pub text: &'a str,
+    pub probability: f32,
+    pub label: &'a str,
+    /// `None` where inputs are classified a batch at a time, so there is no
+    /// per-input latency.
+    pub latency_ms: Option<f64>,
+}
+
+/// The delimited layout selected with `--output-fields` and
+/// `--output-delimiter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchLineFormat {
+    /// The `--output-fields` list; `None` writes every field.
+    pub fields: Option<OutputFields>,
+    pub delimiter: char,
+}
+
+impl BatchLineFormat {
+    /// The layout for the two flags, or `None` when neither is given and
+    /// the harness keeps its default result lines.
+    pub fn from_flags(fields: Option<OutputFields>, delimiter: Option<char>) -> Option<Self> {
+        if fields.is_none() && delimiter.is_none() {
+            return None;
+        }
+        Some(BatchLineFormat { fields, delimiter: delimiter.unwrap_or(DEFAULT_OUTPUT_DELIMITER) })
+    }
+
+    /// The fields written, in order.
+    pub fn fields(&self) -> &[OutputField] {
+        match &self.fields {
+            Some(fields) => &fields.0,
+            None => &OutputField::ALL,
+        }
+    }
+
+    /// The values of `line` for `fields()`: the probability to six decimals
+    /// and the latency to three, empty when it is not known.
+    pub fn values(&self, line: &BatchLine) -> Vec<String> {
+        self.fields()
+            .iter()
+            .map(|field| match field {
+                OutputField::Index => line.index.to_string(),
+                OutputField::Text => line.text.to_string(),
+                OutputField::Probability => format!("{:.6}", line.probability),
+                OutputField::Label => line.label.to_string(),
+                OutputField::Latency => line.latency_ms.map(|ms| format!("{:.3}", ms)).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// `line` as one result line. Values are not quoted, so the delimiter
+    /// should not occur in the texts.
+    pub fn format(&self, line: &BatchLine) -> String {
+        self.values(line).join(&self.delimiter.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> BatchLine<'static> {
+        BatchLine { index: 3, text: "great product", probability: 0.91234567, label: "Positive", latency_ms: Some(0.41251) }
+    }
+
+    #[test]
+    fn no_flags_keep_the_default_layout() {
+        assert_eq!(BatchLineFormat::from_flags(None, None), None);
+    }
+
+    #[test]
+    fn a_delimiter_alone_writes_every_field() {
+        let format = BatchLineFormat::from_flags(None, Some(',')).unwrap();
+        assert_eq!(format.format(&line()), "3,great product,0.912346,Positive,0.413");
+    }
+
+    #[test]
+    fn fields_alone_are_tab_separated_in_the_given_order() {
+        let fields: OutputFields = "label, index,probability".parse().unwrap();
+        let format = BatchLineFormat::from_flags(Some(fields), None).unwrap();
+        assert_eq!(format.format(&line()), "Positive\t3\t0.912346");
+    }
+
+    #[test]
+    fn an_unknown_latency_is_an_empty_value() {
+        let format = BatchLineFormat::from_flags(Some("index,latency".parse().unwrap()), Some('|')).unwrap();
+        assert_eq!(format.format(&BatchLine { latency_ms: None, ..line() }), "3|");
+    }
+
+    #[test]
+    fn rejects_unknown_duplicate_and_missing_fields() {
+        assert!("index,score".parse::<OutputFields>().unwrap_err().contains("unknown output field 'score'"));
+        assert_eq!("label,label".parse::<OutputFields>().unwrap_err(), "output field 'label' is listed twice");
+        assert!(" , ".parse::<OutputFields>().is_err());
+        assert_eq!("text,latency".parse::<OutputFields>().unwrap().to_string(), "text,latency");
+    }
+
+    #[test]
+    fn parses_delimiters() {
+        assert_eq!(parse_output_delimiter(";"), Ok(';'));
+        assert_eq!(parse_output_delimiter("\\t"), Ok('\t'));
+        assert_eq!(parse_output_delimiter("\t"), Ok('\t'));
+        assert!(parse_output_delimiter("").is_err());
+        assert!(parse_output_delimiter("::").is_err());
+        assert!(parse_output_delimiter("\n").is_err());
+    }
+}
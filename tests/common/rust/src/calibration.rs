@@ -0,0 +1,164 @@
+//! Post-hoc probability calibration loaded from a JSON file.
+//!
+//! The file maps class labels to a calibration map. The map type is picked
+//! per class from the fields present:
+//!
+//! * `{"a": A, "b": B}` is a Platt (sigmoid) map: `1 / (1 + exp(A * p + B))`.
+//! * `{"x": [...], "y": [...]}` is an isotonic step function: `p` maps to
+//!   the `y` of the largest threshold in `x` that is `<= p`, and to `y[0]`
+//!   below the first threshold. `x` must be sorted ascending.
+//!
+//! Classes without an entry are reported uncalibrated.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CalibrationMap {
+    Platt { a: f32, b: f32 },
+    Isotonic { x: Vec<f32>, y: Vec<f32> },
+}
+
+impl CalibrationMap {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CalibrationMap::Platt { .. } => "platt",
+            CalibrationMap::Isotonic { .. } => "isotonic",
+        }
+    }
+
+    pub fn apply(&self, probability: f32) -> f32 {
+        match self {
+            CalibrationMap::Platt { a, b } => 1.0 / (1.0 + (a * probability + b).exp()),
+            CalibrationMap::Isotonic { x, y } => {
+                let step = x.partition_point(|&threshold| threshold <= probability);
+                y[step.saturating_sub(1)]
+            }
+        }
+    }
+
+    fn validate(&self, label: &str) -> Result<()> {
+        if let CalibrationMap::Isotonic { x, y } = self {
+            if x.is_empty() || x.len() != y.len() {
+                bail!(
+                    "isotonic map for '{}' needs matching non-empty x and y (got {} and {})",
+                    label, x.len(), y.len()
+                );
+            }
+            if x.windows(2).any(|pair| pair[0] > pair[1]) {
+                bail!("isotonic thresholds for '{}' are not sorted ascending", label);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Calibration maps keyed by class label.
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    maps: HashMap<String, CalibrationMap>,
+}
+
+impl Calibration {
+    pub fn load(path: &str) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open calibration file '{}'", path))?;
+        let maps: HashMap<String, CalibrationMap> = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("invalid calibration file '{}'", path))?;
+        for (label, map) in &maps {
+            map.validate(label)?;
+        }
+        Ok(Calibration { maps })
+    }
+
+    /// Fails if the file names a class the model does not have.
+    pub fn check_labels<S: AsRef<str>>(&self, classes: &[S]) -> Result<()> {
+        for label in self.maps.keys() {
+            if !classes.iter().any(|class| class.as_ref() == label) {
+                bail!("calibration file has a map for unknown class '{}'", label);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply(&self, label: &str, probability: f32) -> f32 {
+        match self.maps.get(label) {
+            Some(map) => map.apply(probability),
+            None => probability,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        let mut labels: Vec<_> = self.maps.iter().collect();
+        labels.sort_by(|a, b| a.0.cmp(b.0));
+        for (label, map) in labels {
+            println!("   {}: {}", label, map.kind());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn isotonic() -> CalibrationMap {
+        CalibrationMap::Isotonic { x: vec![0.2, 0.5, 0.8], y: vec![0.1, 0.4, 0.9] }
+    }
+
+    #[test]
+    fn platt_map_applies_the_sigmoid() {
+        let map = CalibrationMap::Platt { a: -2.0, b: 1.0 };
+        assert!((map.apply(0.5) - 0.5).abs() < 1e-6);
+        let expected = 1.0 / (1.0 + (-2.0f32 * 0.9 + 1.0).exp());
+        assert!((map.apply(0.9) - expected).abs() < 1e-6);
+        assert!(map.apply(0.9) > map.apply(0.1));
+    }
+
+    #[test]
+    fn isotonic_map_steps_at_each_threshold() {
+        let map = isotonic();
+        assert_eq!(map.apply(0.2), 0.1);
+        assert_eq!(map.apply(0.49), 0.1);
+        assert_eq!(map.apply(0.5), 0.4);
+        assert_eq!(map.apply(0.79), 0.4);
+        assert_eq!(map.apply(0.8), 0.9);
+    }
+
+    #[test]
+    fn isotonic_map_clamps_outside_the_thresholds() {
+        let map = isotonic();
+        assert_eq!(map.apply(0.0), 0.1);
+        assert_eq!(map.apply(-1.0), 0.1);
+        assert_eq!(map.apply(1.0), 0.9);
+        assert_eq!(map.apply(5.0), 0.9);
+    }
+
+    #[test]
+    fn malformed_isotonic_maps_are_rejected() {
+        let unsorted = CalibrationMap::Isotonic { x: vec![0.5, 0.2], y: vec![0.1, 0.4] };
+        assert!(unsorted.validate("Positive").is_err());
+        let mismatched = CalibrationMap::Isotonic { x: vec![0.2, 0.5], y: vec![0.1] };
+        assert!(mismatched.validate("Positive").is_err());
+        let empty = CalibrationMap::Isotonic { x: vec![], y: vec![] };
+        assert!(empty.validate("Positive").is_err());
+        assert!(isotonic().validate("Positive").is_ok());
+    }
+
+    #[test]
+    fn parses_both_map_kinds_and_passes_through_unmapped_classes() {
+        let maps: HashMap<String, CalibrationMap> = serde_json::from_str(
+            r#"{"Positive": {"a": -2.0, "b": 1.0}, "sports": {"x": [0.2, 0.5], "y": [0.1, 0.4]}}"#,
+        )
+        .unwrap();
+        let calibration = Calibration { maps };
+        assert_eq!(calibration.maps["Positive"].kind(), "platt");
+        assert_eq!(calibration.maps["sports"].kind(), "isotonic");
+        assert_eq!(calibration.apply("business", 0.37), 0.37);
+        assert!(calibration.check_labels(&["Positive", "sports", "business"]).is_ok());
+        assert!(calibration.check_labels(&["Positive"]).is_err());
+    }
+}
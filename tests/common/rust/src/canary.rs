@@ -0,0 +1,130 @@
+//! The `--canary` startup self-check of the serve modes: texts with the
+//! labels they must get, all classified before any traffic is accepted so
+//! a broken model or vocab fails at boot instead of serving wrong labels.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufReader;
+
+/// One entry of a `--canary` file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Canary {
+    pub text: String,
+    pub expected_label: String,
+}
+
+/// Reads a `--canary` file: a JSON array of
+/// `{"text": ..., "expected_label": ...}` objects. An empty array or an
+/// entry with a blank text is an error, as the check would prove nothing.
+pub fn read_canaries(path: &str) -> Result<Vec<Canary>> {
+    let file = File::open(path).with_context(|| format!("failed to open canary file '{}'", path))?;
+    let canaries: Vec<Canary> = serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("'{}' is not a JSON array of {{\"text\", \"expected_label\"}} objects", path))?;
+    if canaries.is_empty() {
+        bail!("'{}' has no canary inputs", path);
+    }
+    if let Some(index) = canaries.iter().position(|canary| canary.text.trim().is_empty()) {
+        bail!("'{}': canary {} has an empty text", path, index + 1);
+    }
+    Ok(canaries)
+}
+
+/// Classifies every canary with `classify` and logs each result to stderr.
+/// Labels are compared ignoring ASCII case, as in `--eval`. Fails once all
+/// have run when any got another label or could not be classified.
+pub fn check_canaries<F>(canaries: &[Canary], mut classify: F) -> Result<()>
+where
+    F: FnMut(&str) -> Result<String>,
+{
+    let mut failed = 0usize;
+    for canary in canaries {
+        match classify(&canary.text) {
+            Ok(label) if label.eq_ignore_ascii_case(&canary.expected_label) => {
+                tracing::info!("✅ Canary passed: '{}' -> {}", canary.text, label);
+            }
+            Ok(label) => {
+                failed += 1;
+                tracing::warn!("❌ Canary failed: '{}' -> {} (expected {})", canary.text, label, canary.expected_label);
+            }
+            Err(e) => {
+                failed += 1;
+                tracing::warn!("❌ Canary failed: '{}' could not be classified: {:#}", canary.text, e);
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} canary inputs failed - not serving", failed, canaries.len());
+    }
+    tracing::info!("🐤 All {} canary inputs passed", canaries.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn canary(text: &str, expected_label: &str) -> Canary {
+        Canary { text: text.to_string(), expected_label: expected_label.to_string() }
+    }
+
+    fn write_canary_file(name: &str, contents: &str) -> (std::path::PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("wl-canary-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("canary.json");
+        std::fs::write(&path, contents).unwrap();
+        let path_str = path.to_string_lossy().into_owned();
+        (dir, path_str)
+    }
+
+    #[test]
+    fn reads_a_canary_file() {
+        let (dir, path) = write_canary_file(
+            "ok",
+            r#"[{"text": "great movie", "expected_label": "Positive"}, {"text": "awful", "expected_label": "Negative"}]"#,
+        );
+        let canaries = read_canaries(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(canaries.unwrap(), vec![canary("great movie", "Positive"), canary("awful", "Negative")]);
+    }
+
+    #[test]
+    fn rejects_malformed_empty_and_blank_canary_files() {
+        for (name, contents, message) in [
+            ("object", r#"{"text": "great", "expected_label": "Positive"}"#, "is not a JSON array"),
+            ("field", r#"[{"text": "great", "label": "Positive"}]"#, "is not a JSON array"),
+            ("empty", "[]", "has no canary inputs"),
+            ("blank", r#"[{"text": " ", "expected_label": "Positive"}]"#, "canary 1 has an empty text"),
+        ] {
+            let (dir, path) = write_canary_file(name, contents);
+            let error = read_canaries(&path).unwrap_err();
+            std::fs::remove_dir_all(&dir).unwrap();
+            assert!(error.to_string().contains(&path), "{}: {}", name, error);
+            assert!(error.to_string().contains(message), "{}: {}", name, error);
+        }
+    }
+
+    #[test]
+    fn every_canary_is_checked_before_failing() {
+        let canaries = [canary("good", "positive"), canary("bad", "Negative"), canary("broken", "Negative")];
+        let mut seen = Vec::new();
+        let result = check_canaries(&canaries, |text| {
+            seen.push(text.to_string());
+            match text {
+                "broken" => Err(anyhow!("model output is NaN")),
+                _ => Ok("Positive".to_string()),
+            }
+        });
+        assert_eq!(seen, ["good", "bad", "broken"]);
+        assert_eq!(result.unwrap_err().to_string(), "2 of 3 canary inputs failed - not serving");
+    }
+
+    #[test]
+    fn matching_canaries_pass() {
+        let canaries = [canary("good", "Positive"), canary("bad", "Negative")];
+        let result = check_canaries(&canaries, |text| Ok(if text == "good" { "Positive" } else { "Negative" }.to_string()));
+        assert!(result.is_ok());
+    }
+}
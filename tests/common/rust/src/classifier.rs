@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// Model files a classifier directory holds, under the names the harnesses
+/// use as their `--model`, `--vocab` and `--scaler` defaults.
+pub const MODEL_FILE_NAMES: [&str; 3] = ["model.onnx", "vocab.json", "scaler.json"];
+
+/// What a `Classifier` returns for one text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ClassificationResult {
+    /// Single-output model: the positive-class probability and the label it
+    /// maps to at the classifier's threshold.
+    Binary { label: String, probability: f32 },
+    /// Multiclass or multi-label model: one probability per label, in model
+    /// output order.
+    Labels(Vec<(String, f32)>),
+}
+
+/// A loaded text classifier. The binary, multiclass and sigmoid harnesses
+/// each implement it, so a caller routing between models can hold any of
+/// them as a `Box<dyn Classifier>`.
+pub trait Classifier: Send + Sync {
+    fn classify(&self, text: &str) -> anyhow::Result<ClassificationResult>;
+}
@@ -0,0 +1,431 @@
+//! Rough compute-cost estimate for an ONNX model.
+//!
+//! ONNX Runtime does not expose the graph, so this walks the model's protobuf
+//! directly and only decodes the handful of fields it needs: node op types
+//! and inputs, initializer dims and the recorded value shapes.
+//!
+//! Only MatMul and Gemm nodes are counted. For each one the cost is
+//! `rows * K * N` multiply-accumulates, where `K x N` comes from the weight
+//! operand and `rows` from the data operand. A dynamic batch dimension is
+//! counted as 1 (the harness scores one text per inference), and when the
+//! data operand's shape was not recorded by the exporter a single row is
+//! assumed. Any other unknown dimension makes the estimate unavailable, and
+//! so does a count that overflows `u64`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashMap;
+
+/// Approximate multiply-accumulate count of the dominant ops.
+#[derive(Debug, Clone, Copy)]
+pub struct ComputeEstimate {
+    pub macs: u64,
+    pub ops: usize,
+}
+
+/// Estimates the MACs per inference of the model at `model_path`.
+/// Returns `Ok(None)` when the model has no MatMul/Gemm nodes or their
+/// shapes are dynamic or unknown, and an error when the count overflows.
+pub fn estimate_macs(model_path: &str) -> Result<Option<ComputeEstimate>> {
+    let bytes = std::fs::read(model_path)
+        .with_context(|| format!("failed to read '{}'", model_path))?;
+    let graph = parse_graph(&bytes)
+        .with_context(|| format!("failed to parse ONNX graph in '{}'", model_path))?;
+    estimate_graph(&graph)
+}
+
+/// Prints the approximate MatMul/Gemm cost of the model, or why it is unavailable.
+pub fn print_compute_estimate(model_path: &str) {
+    match estimate_macs(model_path) {
+        Ok(Some(estimate)) => println!(
+            "🧮 Estimated compute: {} MACs per inference ({} MatMul/Gemm ops, approximate)",
+            estimate.macs, estimate.ops
+        ),
+        Ok(None) => println!("🧮 Estimated compute: unavailable (no MatMul/Gemm ops with static shapes)"),
+        Err(e) => println!("🧮 Estimated compute: unavailable ({:#})", e),
+    }
+    println!();
+}
+
+fn estimate_graph(graph: &Graph) -> Result<Option<ComputeEstimate>> {
+    let mut estimate = ComputeEstimate { macs: 0, ops: 0 };
+    for node in &graph.nodes {
+        if node.op_type != "MatMul" && node.op_type != "Gemm" {
+            continue;
+        }
+        let (Some(data), Some(weight)) = (node.inputs.first(), node.inputs.get(1)) else {
+            return Ok(None);
+        };
+        let Some(dims) = graph.shapes.get(weight) else {
+            return Ok(None);
+        };
+        let Some(weight_macs) = weight_macs(dims)? else {
+            return Ok(None);
+        };
+        let rows = match graph.shapes.get(data) {
+            Some(dims) => match data_rows(dims, node.trans_a)? {
+                Some(rows) => rows,
+                None => return Ok(None),
+            },
+            None => 1,
+        };
+        estimate.macs = rows
+            .checked_mul(weight_macs)
+            .and_then(|macs| estimate.macs.checked_add(macs))
+            .ok_or_else(overflow)?;
+        estimate.ops += 1;
+    }
+
+    Ok((estimate.ops > 0).then_some(estimate))
+}
+
+fn overflow() -> anyhow::Error {
+    anyhow!("MAC count overflows u64")
+}
+
+/// `K * N` from the weight operand's trailing two dims (or `K` for a vector).
+fn weight_macs(dims: &[Option<u64>]) -> Result<Option<u64>> {
+    let start = dims.len().saturating_sub(2);
+    let mut macs = 1u64;
+    for dim in &dims[start..] {
+        let Some(d) = dim else {
+            return Ok(None);
+        };
+        macs = macs.checked_mul(*d).ok_or_else(overflow)?;
+    }
+    Ok(Some(macs))
+}
+
+/// Number of rows multiplied against the weights: every dim but the
+/// contracted one, with a dynamic leading batch dim counted as 1.
+fn data_rows(dims: &[Option<u64>], trans_a: bool) -> Result<Option<u64>> {
+    let contracted = if trans_a { dims.len().checked_sub(2) } else { dims.len().checked_sub(1) };
+    let Some(contracted) = contracted else {
+        return Ok(None);
+    };
+    let mut rows = 1u64;
+    for (i, dim) in dims.iter().enumerate() {
+        if i == contracted {
+            continue;
+        }
+        match dim {
+            Some(d) => rows = rows.checked_mul(*d).ok_or_else(overflow)?,
+            None if i == 0 => {}
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(rows))
+}
+
+struct Node {
+    op_type: String,
+    inputs: Vec<String>,
+    trans_a: bool,
+}
+
+struct Graph {
+    nodes: Vec<Node>,
+    shapes: HashMap<String, Vec<Option<u64>>>,
+}
+
+fn parse_graph(model: &[u8]) -> Result<Graph> {
+    // ModelProto.graph = 7
+    let mut graph_bytes = None;
+    for field in Fields::new(model) {
+        if let (7, Wire::Bytes(bytes)) = field? {
+            graph_bytes = Some(bytes);
+        }
+    }
+    let Some(graph_bytes) = graph_bytes else {
+        bail!("model has no graph");
+    };
+
+    let mut graph = Graph { nodes: Vec::new(), shapes: HashMap::new() };
+    for field in Fields::new(graph_bytes) {
+        match field? {
+            // GraphProto.node = 1
+            (1, Wire::Bytes(bytes)) => graph.nodes.push(parse_node(bytes)?),
+            // GraphProto.initializer = 5
+            (5, Wire::Bytes(bytes)) => {
+                let (name, dims) = parse_tensor_dims(bytes)?;
+                graph.shapes.insert(name, dims.into_iter().map(Some).collect());
+            }
+            // GraphProto.input = 11, output = 12, value_info = 13
+            (11..=13, Wire::Bytes(bytes)) => {
+                if let Some((name, dims)) = parse_value_info(bytes)? {
+                    graph.shapes.entry(name).or_insert(dims);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(graph)
+}
+
+fn parse_node(bytes: &[u8]) -> Result<Node> {
+    let mut node = Node { op_type: String::new(), inputs: Vec::new(), trans_a: false };
+    for field in Fields::new(bytes) {
+        match field? {
+            // NodeProto.input = 1, op_type = 4, attribute = 5
+            (1, Wire::Bytes(name)) => node.inputs.push(String::from_utf8_lossy(name).into_owned()),
+            (4, Wire::Bytes(op)) => node.op_type = String::from_utf8_lossy(op).into_owned(),
+            (5, Wire::Bytes(attribute)) => {
+                let mut name: &[u8] = &[];
+                let mut value = 0;
+                for field in Fields::new(attribute) {
+                    match field? {
+                        // AttributeProto.name = 1, i = 3
+                        (1, Wire::Bytes(bytes)) => name = bytes,
+                        (3, Wire::Varint(v)) => value = v,
+                        _ => {}
+                    }
+                }
+                if name == b"transA" {
+                    node.trans_a = value != 0;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(node)
+}
+
+fn parse_tensor_dims(bytes: &[u8]) -> Result<(String, Vec<u64>)> {
+    let mut name = String::new();
+    let mut dims = Vec::new();
+    for field in Fields::new(bytes) {
+        match field? {
+            // TensorProto.dims = 1, either packed or one varint per dim
+            (1, Wire::Varint(dim)) => dims.push(dim),
+            (1, Wire::Bytes(packed)) => {
+                let mut reader = Reader { buf: packed, pos: 0 };
+                while reader.pos < packed.len() {
+                    dims.push(reader.varint()?);
+                }
+            }
+            // TensorProto.name = 8
+            (8, Wire::Bytes(bytes)) => name = String::from_utf8_lossy(bytes).into_owned(),
+            _ => {}
+        }
+    }
+    Ok((name, dims))
+}
+
+/// Decodes ValueInfoProto -> TypeProto.tensor_type -> shape -> dims.
+/// Returns `None` for non-tensor values or values without a recorded shape.
+fn parse_value_info(bytes: &[u8]) -> Result<Option<(String, Vec<Option<u64>>)>> {
+    let mut name = String::new();
+    let mut shape = None;
+    for field in Fields::new(bytes) {
+        match field? {
+            (1, Wire::Bytes(bytes)) => name = String::from_utf8_lossy(bytes).into_owned(),
+            (2, Wire::Bytes(type_proto)) => {
+                for field in Fields::new(type_proto) {
+                    if let (1, Wire::Bytes(tensor_type)) = field? {
+                        for field in Fields::new(tensor_type) {
+                            if let (2, Wire::Bytes(shape_proto)) = field? {
+                                shape = Some(parse_shape(shape_proto)?);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(shape.map(|dims| (name, dims)))
+}
+
+fn parse_shape(bytes: &[u8]) -> Result<Vec<Option<u64>>> {
+    let mut dims = Vec::new();
+    for field in Fields::new(bytes) {
+        if let (1, Wire::Bytes(dimension)) = field? {
+            // Dimension.dim_value = 1; a dim_param (symbolic) leaves it unknown
+            let mut value = None;
+            for field in Fields::new(dimension) {
+                if let (1, Wire::Varint(v)) = field? {
+                    value = Some(v);
+                }
+            }
+            dims.push(value);
+        }
+    }
+    Ok(dims)
+}
+
+enum Wire<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let Some(&byte) = self.buf.get(self.pos) else {
+                bail!("truncated varint");
+            };
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("varint too long")
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.buf.len());
+        let Some(end) = end else {
+            bail!("truncated field");
+        };
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+}
+
+/// Iterator over the top-level `(field number, value)` pairs of a message.
+struct Fields<'a> {
+    reader: Reader<'a>,
+    failed: bool,
+}
+
+impl<'a> Fields<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Fields { reader: Reader { buf, pos: 0 }, failed: false }
+    }
+
+    fn read_field(&mut self) -> Result<(u64, Wire<'a>)> {
+        let key = self.reader.varint()?;
+        let wire = match key & 0x7 {
+            0 => Wire::Varint(self.reader.varint()?),
+            1 => {
+                self.reader.take(8)?;
+                Wire::Fixed
+            }
+            2 => {
+                let len = self.reader.varint()? as usize;
+                Wire::Bytes(self.reader.take(len)?)
+            }
+            5 => {
+                self.reader.take(4)?;
+                Wire::Fixed
+            }
+            other => bail!("unsupported protobuf wire type {}", other),
+        };
+        Ok((key >> 3, wire))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u64, Wire<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.reader.pos >= self.reader.buf.len() {
+            return None;
+        }
+        let field = self.read_field();
+        self.failed = field.is_err();
+        Some(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(number: u64, bytes: &[u8], out: &mut Vec<u8>) {
+        varint(number << 3 | 2, out);
+        varint(bytes.len() as u64, out);
+        out.extend_from_slice(bytes);
+    }
+
+    fn varint_field(number: u64, value: u64, out: &mut Vec<u8>) {
+        varint(number << 3, out);
+        varint(value, out);
+    }
+
+    /// A model with one `MatMul(x, W)`, `W` a `k x n` initializer and `x` a
+    /// `[batch, k]` graph input with a symbolic batch dim.
+    fn matmul_model(k: u64, n: u64) -> Vec<u8> {
+        let mut node = Vec::new();
+        bytes_field(1, b"x", &mut node);
+        bytes_field(1, b"W", &mut node);
+        bytes_field(4, b"MatMul", &mut node);
+
+        let mut initializer = Vec::new();
+        varint_field(1, k, &mut initializer);
+        varint_field(1, n, &mut initializer);
+        bytes_field(8, b"W", &mut initializer);
+
+        let mut batch = Vec::new();
+        bytes_field(2, b"batch", &mut batch);
+        let mut features = Vec::new();
+        varint_field(1, k, &mut features);
+        let mut shape = Vec::new();
+        bytes_field(1, &batch, &mut shape);
+        bytes_field(1, &features, &mut shape);
+        let mut tensor_type = Vec::new();
+        bytes_field(2, &shape, &mut tensor_type);
+        let mut type_proto = Vec::new();
+        bytes_field(1, &tensor_type, &mut type_proto);
+        let mut input = Vec::new();
+        bytes_field(1, b"x", &mut input);
+        bytes_field(2, &type_proto, &mut input);
+
+        let mut graph = Vec::new();
+        bytes_field(1, &node, &mut graph);
+        bytes_field(5, &initializer, &mut graph);
+        bytes_field(11, &input, &mut graph);
+        let mut model = Vec::new();
+        bytes_field(7, &graph, &mut model);
+        model
+    }
+
+    #[test]
+    fn counts_a_matmul_with_a_dynamic_batch_dim() {
+        let graph = parse_graph(&matmul_model(5000, 2)).unwrap();
+        let estimate = estimate_graph(&graph).unwrap().unwrap();
+        assert_eq!(estimate.macs, 10_000);
+        assert_eq!(estimate.ops, 1);
+    }
+
+    #[test]
+    fn overflowing_weight_dims_are_an_error() {
+        let graph = parse_graph(&matmul_model(u64::MAX / 2, 3)).unwrap();
+        assert!(estimate_graph(&graph).is_err());
+        assert!(weight_macs(&[Some(u64::MAX), Some(2)]).is_err());
+    }
+
+    #[test]
+    fn overflowing_rows_and_totals_are_errors() {
+        assert!(data_rows(&[Some(u64::MAX), Some(2), Some(8)], false).is_err());
+        assert_eq!(data_rows(&[None, Some(3), Some(8)], false).unwrap(), Some(3));
+
+        let mut graph = parse_graph(&matmul_model(u64::MAX / 4, 2)).unwrap();
+        graph.nodes.push(Node { op_type: "MatMul".into(), inputs: vec!["x".into(), "W".into()], trans_a: false });
+        graph.nodes.push(Node { op_type: "MatMul".into(), inputs: vec!["x".into(), "W".into()], trans_a: false });
+        graph.nodes.push(Node { op_type: "MatMul".into(), inputs: vec!["x".into(), "W".into()], trans_a: false });
+        assert!(estimate_graph(&graph).is_err());
+    }
+
+    #[test]
+    fn unknown_inner_dims_make_the_estimate_unavailable() {
+        assert_eq!(weight_macs(&[None, Some(2)]).unwrap(), None);
+        assert_eq!(data_rows(&[Some(1), None, Some(8)], false).unwrap(), None);
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+/// Opens a model data file (vocab, scaler or labels) for buffered reading.
+/// Paths ending in `.gz` are decompressed on the fly, so `vocab.json.gz`
+/// reads the same as `vocab.json`; any other path is read as it is.
+pub fn open_data_file<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::{Read, Write};
+
+    fn read_all(path: &Path) -> String {
+        let mut contents = String::new();
+        open_data_file(path).unwrap().read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn gzipped_and_plain_vocab_read_the_same() {
+        let dir = std::env::temp_dir().join(format!("whitelightning-data-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let vocab = r#"{"vocab": {"good": 0, "movie": 1}, "idf": [1.0, 1.5]}"#;
+
+        let plain = dir.join("vocab.json");
+        std::fs::write(&plain, vocab).unwrap();
+        let gzipped = dir.join("vocab.json.gz");
+        let mut encoder = GzEncoder::new(File::create(&gzipped).unwrap(), Compression::default());
+        encoder.write_all(vocab.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let (plain_contents, gzipped_contents) = (read_all(&plain), read_all(&gzipped));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(plain_contents, vocab);
+        assert_eq!(gzipped_contents, vocab);
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::{self, BufRead, ErrorKind};
+use std::path::Path;
+
+use crate::data_file::open_data_file;
+
+/// One row of an `--eval` file: the text and the label it should get.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalCase {
+    pub text: String,
+    pub expected: String,
+}
+
+/// Reads an `--eval` file of `text<TAB>label` lines. Blank lines are
+/// skipped, the label is the part after the last tab, and a line without a
+/// tab is an error naming its line number. A `.gz` path is decompressed.
+pub fn read_eval_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<EvalCase>> {
+    let path = path.as_ref();
+    let mut cases = Vec::new();
+    for (index, line) in open_data_file(path)?.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((text, expected)) = line.rsplit_once('\t') else {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("{}:{}: expected 'text<TAB>label'", path.display(), index + 1),
+            ));
+        };
+        cases.push(EvalCase { text: text.to_string(), expected: expected.trim().to_string() });
+    }
+    Ok(cases)
+}
+
+/// Confusion matrix over string labels, with accuracy and per-class
+/// precision and recall. Labels that differ only in ASCII case count as the
+/// same class and keep the spelling they were first seen with.
+#[derive(Debug, Clone, Default)]
+pub struct ClassificationReport {
+    labels: Vec<String>,
+    /// `counts[expected][predicted]`, indexed like `labels`.
+    counts: Vec<Vec<usize>>,
+}
+
+impl ClassificationReport {
+    pub fn add(&mut self, expected: &str, predicted: &str) {
+        let expected = self.label_index(expected);
+        let predicted = self.label_index(predicted);
+        self.counts[expected][predicted] += 1;
+    }
+
+    fn label_index(&mut self, label: &str) -> usize {
+        if let Some(index) = self.labels.iter().position(|known| known.eq_ignore_ascii_case(label)) {
+            return index;
+        }
+        self.labels.push(label.to_string());
+        for row in &mut self.counts {
+            row.push(0);
+        }
+        self.counts.push(vec![0; self.labels.len()]);
+        self.labels.len() - 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    pub fn correct(&self) -> usize {
+        (0..self.labels.len()).map(|i| self.counts[i][i]).sum()
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.total() == 0 { 0.0 } else { self.correct() as f64 / self.total() as f64 }
+    }
+
+    /// Share of predictions of class `index` that were right; `None` when
+    /// the class was never predicted.
+    fn precision(&self, index: usize) -> Option<f64> {
+        let predicted: usize = self.counts.iter().map(|row| row[index]).sum();
+        (predicted > 0).then(|| self.counts[index][index] as f64 / predicted as f64)
+    }
+
+    /// Share of rows expecting class `index` that got it; `None` when no row
+    /// expected the class.
+    fn recall(&self, index: usize) -> Option<f64> {
+        let expected: usize = self.counts[index].iter().sum();
+        (expected > 0).then(|| self.counts[index][index] as f64 / expected as f64)
+    }
+
+    pub fn print(&self) {
+        let format_ratio = |ratio: Option<f64>| ratio.map_or_else(|| "n/a".to_string(), |ratio| format!("{:.1}%", ratio * 100.0));
+        let name_width = self.labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(5);
+
+        println!("📋 EVALUATION RESULTS:");
+        println!("   Accuracy: {}/{} ({:.1}%)", self.correct(), self.total(), self.accuracy() * 100.0);
+        println!();
+
+        println!("   {:<name_width$}  {:>9}  {:>7}  {:>7}", "Class", "Precision", "Recall", "Support");
+        for (i, label) in self.labels.iter().enumerate() {
+            let support: usize = self.counts[i].iter().sum();
+            println!("   {:<name_width$}  {:>9}  {:>7}  {:>7}",
+                     label, format_ratio(self.precision(i)), format_ratio(self.recall(i)), support);
+        }
+        println!();
+
+        println!("   Confusion matrix (rows: expected, columns: predicted):");
+        let header: Vec<String> = self.labels.iter().map(|label| format!("{:>name_width$}", label)).collect();
+        println!("   {:<name_width$}  {}", "", header.join("  "));
+        for (label, row) in self.labels.iter().zip(&self.counts) {
+            let cells: Vec<String> = row.iter().map(|count| format!("{:>name_width$}", count)).collect();
+            println!("   {:<name_width$}  {}", label, cells.join("  "));
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_class_precision_and_recall_follow_the_confusion_matrix() {
+        let mut report = ClassificationReport::default();
+        for (expected, predicted) in [
+            ("spam", "spam"),
+            ("spam", "spam"),
+            ("spam", "ham"),
+            ("ham", "ham"),
+            ("ham", "SPAM"),
+            ("other", "ham"),
+        ] {
+            report.add(expected, predicted);
+        }
+
+        assert_eq!(report.labels, ["spam", "ham", "other"]);
+        assert_eq!(report.total(), 6);
+        assert_eq!(report.correct(), 3);
+        assert_eq!(report.accuracy(), 0.5);
+
+        // spam: 2 of 3 spam predictions right, 2 of 3 spam rows found
+        assert_eq!(report.precision(0), Some(2.0 / 3.0));
+        assert_eq!(report.recall(0), Some(2.0 / 3.0));
+        // ham: 1 of 3 ham predictions right, 1 of 2 ham rows found
+        assert_eq!(report.precision(1), Some(1.0 / 3.0));
+        assert_eq!(report.recall(1), Some(0.5));
+        // other was expected once but never predicted
+        assert_eq!(report.precision(2), None);
+        assert_eq!(report.recall(2), Some(0.0));
+    }
+
+    #[test]
+    fn an_empty_report_has_zero_accuracy() {
+        let report = ClassificationReport::default();
+        assert_eq!(report.total(), 0);
+        assert_eq!(report.accuracy(), 0.0);
+    }
+}
@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Hex SHA-256 of the file at `path`. The file is streamed through the
+/// hasher, so a multi-hundred-MB model is never held in memory at once.
+pub fn file_sha256<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = path.as_ref();
+    let mut file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("cannot hash '{}': {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Path and SHA-256 of one input file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileHash {
+    pub path: String,
+    pub sha256: String,
+}
+
+impl FileHash {
+    pub fn compute(path: &str) -> io::Result<Self> {
+        Ok(FileHash { path: path.to_string(), sha256: file_sha256(path)? })
+    }
+}
+
+/// Hashes of the model, vocab and scaler files a harness loaded, so a
+/// latency change can be tied to a model swap.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelFileHashes {
+    pub model: FileHash,
+    pub vocab: FileHash,
+    pub scaler: FileHash,
+}
+
+impl ModelFileHashes {
+    pub fn compute(model_path: &str, vocab_path: &str, scaler_path: &str) -> io::Result<Self> {
+        Ok(ModelFileHashes {
+            model: FileHash::compute(model_path)?,
+            vocab: FileHash::compute(vocab_path)?,
+            scaler: FileHash::compute(scaler_path)?,
+        })
+    }
+
+    pub fn print(&self) {
+        println!("🔐 MODEL FILES (SHA-256):");
+        for file in [&self.model, &self.vocab, &self.scaler] {
+            println!("   {}: {}", file.path, file.sha256);
+        }
+        println!();
+    }
+}
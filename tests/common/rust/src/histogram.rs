@@ -0,0 +1,78 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use serde::Serialize;
+
+use crate::bar::{probability_bar, terminal_width};
+
+/// Upper bounds in ms of the `LatencyHistogram` buckets: a fixed 1-2-5 log
+/// scale from 0.1ms to 10s, so histograms from different runs line up.
+pub const LATENCY_HISTOGRAM_BOUNDS_MS: [f64; 16] =
+    [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0];
+
+/// Per-prediction latencies of a run counted into log-spaced buckets, so the
+/// shape of the tail is visible and not just its percentiles.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyHistogram {
+    /// Upper bound of each bucket, inclusive; `LATENCY_HISTOGRAM_BOUNDS_MS`.
+    pub bounds_ms: Vec<f64>,
+    /// `counts[i]` counts latencies in `(bounds_ms[i - 1], bounds_ms[i]]`.
+    /// The extra last entry counts latencies above the largest bound.
+    pub counts: Vec<usize>,
+}
+
+impl LatencyHistogram {
+    pub fn from_latencies(latencies_ms: &[f64]) -> Self {
+        let mut counts = vec![0; LATENCY_HISTOGRAM_BOUNDS_MS.len() + 1];
+        for &latency in latencies_ms {
+            counts[LATENCY_HISTOGRAM_BOUNDS_MS.partition_point(|&bound| bound < latency)] += 1;
+        }
+        LatencyHistogram { bounds_ms: LATENCY_HISTOGRAM_BOUNDS_MS.to_vec(), counts }
+    }
+
+    /// Writes the bounds and counts as pretty-printed JSON to `path`.
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let file = File::create(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("cannot write histogram '{}': {}", path, e)))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// Label of bucket `index`, e.g. `<= 5ms` or `> 10000ms`.
+    fn bucket_label(&self, index: usize) -> String {
+        match self.bounds_ms.get(index) {
+            Some(bound) => format!("<= {}ms", bound),
+            None => format!("> {}ms", self.bounds_ms.last().copied().unwrap_or_default()),
+        }
+    }
+
+    /// Prints one bar per bucket, from the fastest to the slowest non-empty
+    /// bucket, scaled to the fullest bucket and the terminal width.
+    pub fn print(&self) {
+        println!("📊 LATENCY HISTOGRAM:");
+        let (Some(first), Some(last)) = (
+            self.counts.iter().position(|&count| count > 0),
+            self.counts.iter().rposition(|&count| count > 0),
+        ) else {
+            println!("   No latencies recorded");
+            println!();
+            return;
+        };
+        let max_count = self.counts[first..=last].iter().copied().max().unwrap_or(1);
+        let labels: Vec<String> = (first..=last).map(|i| self.bucket_label(i)).collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+        let count_width = max_count.to_string().len();
+        // Indent, label, two spaces, count and a space come before the bar
+        let bar_width = terminal_width().saturating_sub(label_width + count_width + 6).max(10);
+        for (label, &count) in labels.iter().zip(&self.counts[first..=last]) {
+            let line = format!(
+                "   {:>label_width$}  {:>count_width$} {}",
+                label, count, probability_bar(count as f32 / max_count as f32, bar_width),
+            );
+            println!("{}", line.trim_end());
+        }
+        println!();
+    }
+}
@@ -0,0 +1,121 @@
+use serde::Serialize;
+
+/// Coefficient of variation (std / mean) of per-prediction latency above
+/// which a run's timings are reported as unstable.
+pub const HIGH_LATENCY_CV: f64 = 0.3;
+
+/// Nearest-rank percentile of an ascending-sorted slice, `q` in `[0, 100]`.
+pub fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((q / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Distribution of the per-prediction latencies of a run, in ms. `std` is
+/// the population standard deviation and `cv` is `std / mean`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub std: f64,
+    pub cv: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl LatencyStats {
+    pub fn from_latencies(latencies_ms: &[f64]) -> Self {
+        if latencies_ms.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = latencies_ms.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let variance = sorted.iter().map(|latency| (latency - mean).powi(2)).sum::<f64>() / count as f64;
+        let std = variance.sqrt();
+        Self {
+            count,
+            mean,
+            min: sorted[0],
+            max: sorted[count - 1],
+            std,
+            cv: if mean > 0.0 { std / mean } else { 0.0 },
+            p50: percentile(&sorted, 50.0),
+            p90: percentile(&sorted, 90.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+        }
+    }
+
+    pub fn is_unstable(&self) -> bool {
+        self.cv > HIGH_LATENCY_CV
+    }
+
+    pub fn print(&self) {
+        println!("⏱️  TIMING ANALYSIS:");
+        println!("   Mean: {:.2}ms", self.mean);
+        println!("   Min: {:.2}ms", self.min);
+        println!("   Max: {:.2}ms", self.max);
+        println!("   Std Dev: {:.2}ms", self.std);
+        println!("   CV: {:.1}%{}", self.cv * 100.0,
+                 if self.is_unstable() { " ⚠️ unstable" } else { "" });
+        println!("   p50: {:.2}ms", self.p50);
+        println!("   p90: {:.2}ms", self.p90);
+        println!("   p95: {:.2}ms", self.p95);
+        println!("   p99: {:.2}ms", self.p99);
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_the_nearest_rank() {
+        let sorted: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 50.0), 5.0);
+        assert_eq!(percentile(&sorted, 99.0), 10.0);
+        assert_eq!(percentile(&sorted, 100.0), 10.0);
+        assert_eq!(percentile(&[7.0], 50.0), 7.0);
+    }
+
+    #[test]
+    fn percentile_of_no_latencies_is_zero() {
+        assert_eq!(percentile(&[], 50.0), 0.0);
+        assert_eq!(percentile(&[], 100.0), 0.0);
+    }
+
+    #[test]
+    fn stats_are_computed_from_unsorted_latencies() {
+        let stats = LatencyStats::from_latencies(&[4.0, 2.0, 8.0, 6.0]);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.min, 2.0);
+        assert_eq!(stats.max, 8.0);
+        // Population variance: (9 + 1 + 1 + 9) / 4 = 5
+        assert!((stats.std - 5.0_f64.sqrt()).abs() < 1e-12);
+        assert!((stats.cv - 5.0_f64.sqrt() / 5.0).abs() < 1e-12);
+        assert_eq!(stats.p50, 4.0);
+        assert_eq!(stats.p99, 8.0);
+        // cv is about 0.45, above HIGH_LATENCY_CV
+        assert!(stats.is_unstable());
+    }
+
+    #[test]
+    fn stats_of_no_latencies_are_zero() {
+        let stats = LatencyStats::from_latencies(&[]);
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.mean, 0.0);
+        assert_eq!(stats.cv, 0.0);
+    }
+}
@@ -0,0 +1,56 @@
+//! Code shared by the Rust ONNX test harnesses: system information,
+//! performance metrics, latency statistics and histograms, CPU/memory
+//! monitoring, benchmark reports and `--log-summary` lines, model compute
+//! estimates, model output checks, prediction spread checks, TF-IDF
+//! vectorization, gzip-aware data file loading, model file hashing,
+//! probability bars, delimited batch result lines, labeled-set evaluation,
+//! `--canary` startup checks, probability calibration, diagnostic logging
+//! and the `Classifier` trait. Every harness prints these blocks the same
+//! way, so they live here instead of in each `main.rs`. With the `session`
+//! feature it also builds ONNX Runtime sessions.
+
+mod bar;
+mod batch_line;
+mod calibration;
+mod canary;
+mod classifier;
+mod compute_cost;
+mod data_file;
+mod eval;
+mod file_hash;
+mod histogram;
+mod latency;
+mod log_summary;
+mod logging;
+mod metrics;
+mod monitor;
+mod output;
+mod prediction_spread;
+mod report;
+#[cfg(feature = "session")]
+mod session;
+mod system_info;
+mod tfidf;
+
+pub use bar::{probability_bar, terminal_width, DEFAULT_TERMINAL_WIDTH};
+pub use batch_line::{parse_output_delimiter, BatchLine, BatchLineFormat, OutputField, OutputFields, DEFAULT_OUTPUT_DELIMITER};
+pub use calibration::{Calibration, CalibrationMap};
+pub use canary::{check_canaries, read_canaries, Canary};
+pub use classifier::{ClassificationResult, Classifier, MODEL_FILE_NAMES};
+pub use compute_cost::{estimate_macs, print_compute_estimate, ComputeEstimate};
+pub use data_file::open_data_file;
+pub use eval::{read_eval_file, ClassificationReport, EvalCase};
+pub use file_hash::{file_sha256, FileHash, ModelFileHashes};
+pub use histogram::{LatencyHistogram, LATENCY_HISTOGRAM_BOUNDS_MS};
+pub use latency::{percentile, LatencyStats, HIGH_LATENCY_CV};
+pub use log_summary::print_log_summary;
+pub use logging::{init_logging, LogFormat, DEFAULT_LOG_FILTER};
+pub use metrics::{PerformanceMetrics, DEFAULT_RATING_TARGET_MS};
+pub use monitor::{memory_usage_mb, CpuMetric, ResourceMonitor, SystemMonitor, DEFAULT_SAMPLE_INTERVAL_MS};
+pub use output::{check_finite_output, check_nonempty_output, check_output_shape, MAX_OUTPUT_RANK};
+pub use prediction_spread::{PredictionSpread, DEFAULT_MIN_OUTPUT_VARIANCE};
+pub use report::{BenchmarkReport, BENCHMARK_REPORT_SCHEMA_VERSION};
+#[cfg(feature = "session")]
+pub use session::{build_session, OptLevel, Provider, SessionConfig};
+pub use system_info::SystemInfo;
+pub use tfidf::{idf_key, vocabulary_object, Norm, TfidfVectorizer, IDF_KEYS, VOCABULARY_KEYS};
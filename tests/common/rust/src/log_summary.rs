@@ -0,0 +1,36 @@
+use crate::file_hash::file_sha256;
+use crate::latency::percentile;
+use anyhow::Result;
+
+/// Prints the one-line logfmt summary for `--log-summary`. The line always
+/// starts with `WL_SUMMARY` and keeps the same keys in every harness so log
+/// pipelines can filter and parse it. A harness that does not measure
+/// memory passes `None`, printed as `memory_delta_mb=na`.
+pub fn print_log_summary(
+    harness: &str,
+    model_path: &str,
+    latencies_ms: &[f64],
+    throughput_per_sec: f64,
+    memory_delta_mb: Option<f64>,
+) -> Result<()> {
+    let mut sorted = latencies_ms.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mean = sorted.iter().sum::<f64>() / sorted.len().max(1) as f64;
+    let memory_delta = match memory_delta_mb {
+        Some(delta) => format!("{:.2}", delta),
+        None => "na".to_string(),
+    };
+    println!(
+        "WL_SUMMARY harness={} model_sha256={} platform={}-{} predictions={} mean_latency_ms={:.3} p95_latency_ms={:.3} throughput_per_sec={:.1} memory_delta_mb={}",
+        harness,
+        file_sha256(model_path)?,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        sorted.len(),
+        mean,
+        percentile(&sorted, 95.0),
+        throughput_per_sec,
+        memory_delta,
+    );
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use tracing_subscriber::EnvFilter;
+
+/// Filter applied when `RUST_LOG` is unset: warnings and status lines, but
+/// not the per-prediction phase timings, which are logged at debug level.
+/// The `ort` crate logs every provider registration at info, so it is held
+/// to warnings.
+pub const DEFAULT_LOG_FILTER: &str = "info,ort=warn";
+
+/// How diagnostics are written to stderr, selected with `--log-format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// The message and its fields only, so warnings read as plain lines in a
+    /// terminal.
+    #[default]
+    Pretty,
+    /// One JSON object per event, with its timestamp, level, target, fields
+    /// and enclosing spans.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown log format '{}' - expected 'pretty' or 'json'", value)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// Installs the global `tracing` subscriber, writing `format` diagnostics to
+/// stderr and filtered by `RUST_LOG`, or `DEFAULT_LOG_FILTER` when it is
+/// unset. Results stay on stdout. Does nothing when a subscriber is already
+/// installed, so the first harness run in a process keeps its format.
+pub fn init_logging(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    // An error only means a subscriber is already installed
+    let _ = match format {
+        LogFormat::Pretty => builder.without_time().with_level(false).with_target(false).try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(true).try_init(),
+    };
+}
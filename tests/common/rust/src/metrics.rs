@@ -0,0 +1,293 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+
+use serde::{Serialize, Serializer};
+
+use crate::latency::HIGH_LATENCY_CV;
+use crate::monitor::CpuMetric;
+
+/// Default latency target for the performance rating.
+pub const DEFAULT_RATING_TARGET_MS: f64 = 100.0;
+
+/// Serializes every field plus the computed `rating_score`, so `--json`
+/// output and reports carry the score the printed block shows.
+#[derive(Debug, Clone, Serialize)]
+#[serde(remote = "Self")]
+pub struct PerformanceMetrics {
+    pub total_time_ms: f64,
+    pub preprocessing_time_ms: f64,
+    /// Preprocessing sub-phases, printed under the preprocessing line when
+    /// the harness measures them separately.
+    pub tokenization_time_ms: Option<f64>,
+    pub vectorization_time_ms: Option<f64>,
+    pub inference_time_ms: f64,
+    pub postprocessing_time_ms: f64,
+    pub memory_start_mb: f64,
+    pub memory_end_mb: f64,
+    pub memory_peak_mb: f64,
+    pub memory_delta_mb: f64,
+    pub cpu_usage_avg: f64,
+    pub cpu_usage_peak: f64,
+    pub cpu_samples: usize,
+    /// Interval between CPU samples, shown next to the sample count.
+    pub cpu_sample_interval_ms: u64,
+    /// How `cpu_usage_avg` and `cpu_usage_peak` combine the per-core usage.
+    /// Only the labels depend on it; the rating uses latency alone.
+    pub cpu_metric: CpuMetric,
+    /// Set when the resource monitor was disabled with `--no-monitor`:
+    /// memory is only read at the start and end, and CPU is not sampled.
+    pub monitoring_disabled: bool,
+    pub throughput_per_sec: f64,
+    /// Number of back-to-back predictions `throughput_per_sec` was measured
+    /// over when it comes from a short burst after a single-text run rather
+    /// than from the run itself.
+    pub throughput_burst: Option<usize>,
+    pub predictions_count: usize,
+    pub rating_target_ms: f64,
+    /// Coefficient of variation of the per-prediction latencies, when the
+    /// run measured more than one. Above `HIGH_LATENCY_CV` the rating is
+    /// shown as a warning.
+    pub latency_cv: Option<f64>,
+}
+
+impl Serialize for PerformanceMetrics {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct WithScore<'a> {
+            #[serde(flatten, serialize_with = "serialize_fields")]
+            metrics: &'a PerformanceMetrics,
+            rating_score: f64,
+        }
+
+        fn serialize_fields<S: Serializer>(metrics: &&PerformanceMetrics, serializer: S) -> Result<S::Ok, S::Error> {
+            PerformanceMetrics::serialize(metrics, serializer)
+        }
+
+        WithScore { metrics: self, rating_score: self.rating_score() }.serialize(serializer)
+    }
+}
+
+impl PerformanceMetrics {
+    pub fn avg_latency_ms(&self) -> f64 {
+        self.total_time_ms / self.predictions_count.max(1) as f64
+    }
+
+    /// Continuous 0-100 rating of the average per-prediction latency:
+    /// `100 * clamp(1 - latency / (2 * target), 0, 1)`. 0ms scores 100, a
+    /// latency equal to the target scores 50 and twice the target or slower
+    /// scores 0. The target is set with `--rating-target-ms`.
+    pub fn rating_score(&self) -> f64 {
+        if self.rating_target_ms <= 0.0 {
+            return 0.0;
+        }
+        100.0 * (1.0 - self.avg_latency_ms() / (2.0 * self.rating_target_ms)).clamp(0.0, 1.0)
+    }
+
+    /// Rating text and emoji for `rating_score()`: EXCELLENT at 90 or more
+    /// (under a fifth of the target), VERY GOOD at 75, GOOD at 50 (on
+    /// target), ACCEPTABLE at 25 and POOR below that.
+    pub fn rating_tier(&self) -> (&'static str, &'static str) {
+        match self.rating_score() {
+            score if score >= 90.0 => ("EXCELLENT", "🚀"),
+            score if score >= 75.0 => ("VERY GOOD", "✅"),
+            score if score >= 50.0 => ("GOOD", "👍"),
+            score if score >= 25.0 => ("ACCEPTABLE", "⚠️"),
+            _ => ("POOR", "❌"),
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format, with a
+    /// `harness` label on every sample. Times stay in milliseconds, as in the
+    /// printed report. CPU usage is left out when no samples were taken.
+    pub fn to_prometheus(&self, harness: &str) -> String {
+        let base = format!("harness=\"{}\"", harness);
+        let with = |label: &str, value: &str| format!("{},{}=\"{}\"", base, label, value);
+        let mut out = String::new();
+
+        let mut phases = vec![
+            (with("phase", "total"), self.total_time_ms),
+            (with("phase", "preprocessing"), self.preprocessing_time_ms),
+        ];
+        if let Some(tokenization_time_ms) = self.tokenization_time_ms {
+            phases.push((with("phase", "tokenization"), tokenization_time_ms));
+        }
+        if let Some(vectorization_time_ms) = self.vectorization_time_ms {
+            phases.push((with("phase", "vectorization"), vectorization_time_ms));
+        }
+        phases.push((with("phase", "inference"), self.inference_time_ms));
+        phases.push((with("phase", "postprocessing"), self.postprocessing_time_ms));
+        push_gauge(&mut out, "whitelightning_phase_time_ms", "Time spent in each phase of the last run, in ms.", &phases);
+
+        push_gauge(&mut out, "whitelightning_avg_latency_ms", "Average time per prediction, in ms.",
+                   &[(base.clone(), self.avg_latency_ms())]);
+        push_gauge(&mut out, "whitelightning_throughput_per_sec", "Predictions per second.",
+                   &[(base.clone(), self.throughput_per_sec)]);
+        push_gauge(&mut out, "whitelightning_predictions", "Predictions made in the last run.",
+                   &[(base.clone(), self.predictions_count as f64)]);
+        push_gauge(&mut out, "whitelightning_memory_mb", "Resident memory of the harness process, in MB.", &[
+            (with("stat", "start"), self.memory_start_mb),
+            (with("stat", "end"), self.memory_end_mb),
+            (with("stat", "peak"), self.memory_peak_mb),
+            (with("stat", "delta"), self.memory_delta_mb),
+        ]);
+        if self.cpu_samples > 0 {
+            let metric = self.cpu_metric.to_string();
+            push_gauge(&mut out, "whitelightning_cpu_usage_percent",
+                       "CPU usage during the last run, combining cores as the metric label says.", &[
+                (format!("{},metric=\"{}\"", with("stat", "avg"), metric), self.cpu_usage_avg),
+                (format!("{},metric=\"{}\"", with("stat", "peak"), metric), self.cpu_usage_peak),
+            ]);
+        }
+        if let Some(cv) = self.latency_cv {
+            push_gauge(&mut out, "whitelightning_latency_cv", "Coefficient of variation of the per-prediction latencies.",
+                       &[(base.clone(), cv)]);
+        }
+        push_gauge(&mut out, "whitelightning_rating_score", "Performance rating score, 0-100.",
+                   &[(base, self.rating_score())]);
+        out
+    }
+
+    /// Writes `to_prometheus(harness)` to `path`, replacing the previous
+    /// contents. The text goes to a temporary file that is then renamed
+    /// over `path`, so a scraper never reads a half-written file.
+    pub fn write_prometheus(&self, path: &str, harness: &str) -> io::Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, self.to_prometheus(harness))
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|e| io::Error::new(e.kind(), format!("cannot write metrics file '{}': {}", path, e)))
+    }
+
+    pub fn print(&self) {
+        println!("📊 PERFORMANCE METRICS:");
+        println!("   Total Processing Time: {:.2}ms", self.total_time_ms);
+        println!("   ├─ Preprocessing: {:.2}ms ({:.1}%)",
+                 self.preprocessing_time_ms,
+                 (self.preprocessing_time_ms / self.total_time_ms) * 100.0);
+        if let (Some(tokenization_time_ms), Some(vectorization_time_ms)) =
+            (self.tokenization_time_ms, self.vectorization_time_ms)
+        {
+            println!("   │  ├─ Tokenization: {:.2}ms ({:.1}%)",
+                     tokenization_time_ms,
+                     (tokenization_time_ms / self.total_time_ms) * 100.0);
+            println!("   │  └─ Vectorization: {:.2}ms ({:.1}%)",
+                     vectorization_time_ms,
+                     (vectorization_time_ms / self.total_time_ms) * 100.0);
+        }
+        println!("   ├─ Model Inference: {:.2}ms ({:.1}%)",
+                 self.inference_time_ms,
+                 (self.inference_time_ms / self.total_time_ms) * 100.0);
+        println!("   └─ Postprocessing: {:.2}ms ({:.1}%)",
+                 self.postprocessing_time_ms,
+                 (self.postprocessing_time_ms / self.total_time_ms) * 100.0);
+        println!();
+
+        println!("🚀 THROUGHPUT:");
+        match self.throughput_burst {
+            Some(burst) => println!("   Predictions per second: {:.2} (measured over a burst of {} predictions, not sustained)",
+                                    self.throughput_per_sec, burst),
+            None => println!("   Predictions per second: {:.2}", self.throughput_per_sec),
+        }
+        println!("   Total predictions: {}", self.predictions_count);
+        println!("   Average time per prediction: {:.2}ms", self.total_time_ms / self.predictions_count as f64);
+        println!();
+
+        println!("💾 MEMORY USAGE:");
+        println!("   Memory Start: {:.2} MB", self.memory_start_mb);
+        println!("   Memory End: {:.2} MB", self.memory_end_mb);
+        if self.monitoring_disabled {
+            println!("   Memory Peak: not sampled (monitoring disabled)");
+        } else {
+            println!("   Memory Peak: {:.2} MB", self.memory_peak_mb);
+        }
+        println!("   Memory Delta: {}{:.2} MB",
+                 if self.memory_delta_mb >= 0.0 { "+" } else { "" },
+                 self.memory_delta_mb);
+        println!();
+
+        println!("🔥 CPU USAGE:");
+        if self.cpu_samples > 0 {
+            println!("   Average CPU: {:.1}% ({})", self.cpu_usage_avg, self.cpu_metric.description());
+            println!("   Peak CPU: {:.1}% ({})", self.cpu_usage_peak, self.cpu_metric.description());
+            println!("   Samples: {} (every {}ms)", self.cpu_samples, self.cpu_sample_interval_ms);
+        } else if self.monitoring_disabled {
+            println!("   CPU monitoring: disabled");
+        } else {
+            println!("   CPU monitoring: Not available");
+        }
+        println!();
+
+        let (rating, emoji) = self.rating_tier();
+
+        let unstable_cv = self.latency_cv.filter(|&cv| cv > HIGH_LATENCY_CV);
+        let emoji = if unstable_cv.is_some() { "⚠️" } else { emoji };
+
+        println!("🎯 PERFORMANCE RATING: {} {}", emoji, rating);
+        println!("   Rating score: {:.1}/100 ({:.2}ms per prediction vs {:.0}ms target)",
+                 self.rating_score(), self.avg_latency_ms(), self.rating_target_ms);
+        if let Some(cv) = unstable_cv {
+            println!("   Latency CV {:.1}% is above {:.0}% - timings are unstable, rerun on a quieter machine",
+                     cv * 100.0, HIGH_LATENCY_CV * 100.0);
+        }
+        println!();
+    }
+}
+
+/// Appends one gauge with its `# HELP` and `# TYPE` lines and a sample per
+/// `(labels, value)` pair.
+fn push_gauge(out: &mut String, name: &str, help: &str, samples: &[(String, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(total_time_ms: f64, predictions_count: usize) -> PerformanceMetrics {
+        PerformanceMetrics {
+            total_time_ms,
+            preprocessing_time_ms: 0.0,
+            tokenization_time_ms: None,
+            vectorization_time_ms: None,
+            inference_time_ms: 0.0,
+            postprocessing_time_ms: 0.0,
+            memory_start_mb: 0.0,
+            memory_end_mb: 0.0,
+            memory_peak_mb: 0.0,
+            memory_delta_mb: 0.0,
+            cpu_usage_avg: 0.0,
+            cpu_usage_peak: 0.0,
+            cpu_samples: 0,
+            cpu_sample_interval_ms: 100,
+            cpu_metric: CpuMetric::default(),
+            monitoring_disabled: false,
+            throughput_per_sec: 0.0,
+            throughput_burst: None,
+            predictions_count,
+            rating_target_ms: DEFAULT_RATING_TARGET_MS,
+            latency_cv: None,
+        }
+    }
+
+    #[test]
+    fn json_carries_the_rating_score() {
+        let json = serde_json::to_value(metrics(50.0, 1)).unwrap();
+        assert_eq!(json["rating_score"], 75.0);
+        assert_eq!(json["total_time_ms"], 50.0);
+        assert_eq!(json["rating_target_ms"], DEFAULT_RATING_TARGET_MS);
+    }
+
+    #[test]
+    fn the_tier_follows_the_per_prediction_latency() {
+        // 1000 predictions in 2s is 2ms each, not a 2000ms run
+        assert_eq!(metrics(2000.0, 1000).rating_tier().0, "EXCELLENT");
+        assert_eq!(metrics(50.0, 1).rating_tier().0, "VERY GOOD");
+        assert_eq!(metrics(100.0, 1).rating_tier().0, "GOOD");
+        assert_eq!(metrics(150.0, 1).rating_tier().0, "ACCEPTABLE");
+        assert_eq!(metrics(200.0, 1).rating_tier().0, "POOR");
+    }
+}
@@ -0,0 +1,328 @@
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use sysinfo::{CpuExt, ProcessExt, System, SystemExt};
+
+/// Samples CPU usage and this process's memory in the background while a run is measured.
+pub trait ResourceMonitor {
+    fn start_monitoring(&self);
+
+    /// Stops sampling and returns
+    /// `(cpu_avg, cpu_peak, cpu_samples, memory_peak_mb, memory_end_mb)`.
+    fn stop_monitoring(&self) -> (f64, f64, usize, f64, f64);
+}
+
+/// How `SystemMonitor` combines per-core CPU usage into one reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuMetric {
+    /// Mean of the per-core percentages: 0-100%, so one busy core on a
+    /// 16-core machine reads about 6%.
+    #[default]
+    Average,
+    /// Sum of the per-core percentages: 100% per fully busy core, up to
+    /// `100% x cores`.
+    Total,
+}
+
+impl CpuMetric {
+    /// How the readings were combined, as printed next to them.
+    pub fn description(self) -> &'static str {
+        match self {
+            CpuMetric::Average => "mean across cores",
+            CpuMetric::Total => "sum across cores, 100% = one core",
+        }
+    }
+}
+
+impl FromStr for CpuMetric {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "average" => Ok(CpuMetric::Average),
+            "total" => Ok(CpuMetric::Total),
+            _ => Err(format!("unknown CPU metric '{}' - expected 'average' or 'total'", value)),
+        }
+    }
+}
+
+impl fmt::Display for CpuMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CpuMetric::Average => "average",
+            CpuMetric::Total => "total",
+        })
+    }
+}
+
+/// Default interval between `SystemMonitor` samples.
+pub const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 50;
+
+/// `ResourceMonitor` backed by `sysinfo`, sampling on a background thread
+/// every `DEFAULT_SAMPLE_INTERVAL_MS` unless configured otherwise.
+///
+/// The sampling thread checks the `monitoring` flag between samples.
+/// `stop_monitoring` clears it, wakes the thread and joins it, so the
+/// closing sample is always taken and no sampler outlives the run or
+/// overlaps the next one.
+pub struct SystemMonitor {
+    system: Arc<Mutex<System>>,
+    monitoring: Arc<AtomicBool>,
+    cpu_readings: Arc<Mutex<Vec<f64>>>,
+    memory_readings: Arc<Mutex<Vec<f64>>>,
+    sampler: Mutex<Option<JoinHandle<()>>>,
+    sample_interval: Duration,
+    pinned_core: Option<usize>,
+    cpu_metric: CpuMetric,
+    enabled: bool,
+}
+
+impl SystemMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: Arc::new(Mutex::new(System::new_all())),
+            monitoring: Arc::new(AtomicBool::new(false)),
+            cpu_readings: Arc::new(Mutex::new(Vec::new())),
+            memory_readings: Arc::new(Mutex::new(Vec::new())),
+            sampler: Mutex::new(None),
+            sample_interval: Duration::from_millis(DEFAULT_SAMPLE_INTERVAL_MS),
+            pinned_core: None,
+            cpu_metric: CpuMetric::Average,
+            enabled: true,
+        }
+    }
+
+    /// Samples every `interval` instead of every `DEFAULT_SAMPLE_INTERVAL_MS`.
+    /// Longer intervals take fewer samples but poll less often, so the
+    /// monitor adds less of its own CPU time to the readings.
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.sample_interval = interval;
+        self
+    }
+
+    /// Pins the sampling thread to CPU core `core`, keeping its polling off
+    /// the cores running inference. If the core does not exist or pinning
+    /// fails, the thread warns on stderr and runs unpinned.
+    pub fn with_pinned_core(mut self, core: usize) -> Self {
+        self.pinned_core = Some(core);
+        self
+    }
+
+    /// Combines per-core usage with `metric` instead of averaging it.
+    pub fn with_cpu_metric(mut self, metric: CpuMetric) -> Self {
+        self.cpu_metric = metric;
+        self
+    }
+
+    /// With `false`, no sampling thread is started, so the monitor adds no
+    /// overhead to the run. `stop_monitoring` then reports zero CPU samples
+    /// and a single end-of-run memory reading as both peak and end.
+    pub fn with_monitoring(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn sample_interval(&self) -> Duration {
+        self.sample_interval
+    }
+
+    pub fn cpu_metric(&self) -> CpuMetric {
+        self.cpu_metric
+    }
+
+    /// Signals the sampling thread, if any, to stop and waits for it to
+    /// take its closing sample and exit.
+    fn stop_sampler(&self) {
+        self.monitoring.store(false, Ordering::Relaxed);
+        if let Some(sampler) = lock(&self.sampler).take() {
+            sampler.thread().unpark();
+            // A panicked sampler leaves its readings behind; `lock`
+            // recovers them from the poisoned mutexes.
+            let _ = sampler.join();
+        }
+    }
+}
+
+impl Drop for SystemMonitor {
+    fn drop(&mut self) {
+        self.stop_sampler();
+    }
+}
+
+/// Locks `mutex` even if a panicking thread poisoned it. The readings stay
+/// valid, since each update is a single push.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceMonitor for SystemMonitor {
+    fn start_monitoring(&self) {
+        // A sampler left over from an unfinished run would keep pushing
+        // readings into this one
+        self.stop_sampler();
+
+        // Clear previous readings
+        lock(&self.cpu_readings).clear();
+        lock(&self.memory_readings).clear();
+        if !self.enabled {
+            return;
+        }
+        self.monitoring.store(true, Ordering::Relaxed);
+
+        let system_clone = Arc::clone(&self.system);
+        let monitoring_clone = Arc::clone(&self.monitoring);
+        let cpu_readings_clone = Arc::clone(&self.cpu_readings);
+        let memory_readings_clone = Arc::clone(&self.memory_readings);
+        let sample_interval = self.sample_interval;
+        let pinned_core = self.pinned_core;
+        let cpu_metric = self.cpu_metric;
+
+        let sampler = thread::spawn(move || {
+            if let Some(core) = pinned_core {
+                let pinned = core_affinity::get_core_ids()
+                    .and_then(|cores| cores.into_iter().find(|core_id| core_id.id == core))
+                    .is_some_and(core_affinity::set_for_current);
+                if !pinned {
+                    tracing::warn!("⚠️ Could not pin the resource monitor to core {} - sampling unpinned", core);
+                }
+            }
+            let sample = || {
+                let mut system = lock(&system_clone);
+                system.refresh_cpu();
+
+                let cpu_total: f64 = system.cpus().iter()
+                    .map(|cpu| cpu.cpu_usage() as f64)
+                    .sum();
+                let cpu_usage = match cpu_metric {
+                    CpuMetric::Average => cpu_total / system.cpus().len().max(1) as f64,
+                    CpuMetric::Total => cpu_total,
+                };
+
+                let memory_usage_mb = process_memory_mb(&mut system);
+
+                lock(&cpu_readings_clone).push(cpu_usage);
+                lock(&memory_readings_clone).push(memory_usage_mb);
+            };
+
+            while monitoring_clone.load(Ordering::Relaxed) {
+                sample();
+                // Parked rather than slept so `stop_sampler` can wake the
+                // thread; the deadline absorbs spurious wakeups
+                let next_sample = Instant::now() + sample_interval;
+                while monitoring_clone.load(Ordering::Relaxed) {
+                    let now = Instant::now();
+                    if now >= next_sample {
+                        break;
+                    }
+                    thread::park_timeout(next_sample - now);
+                }
+            }
+            // Closing sample, so the end-of-run memory reflects the stop time
+            sample();
+        });
+        *lock(&self.sampler) = Some(sampler);
+    }
+
+    fn stop_monitoring(&self) -> (f64, f64, usize, f64, f64) {
+        self.stop_sampler();
+        if !self.enabled {
+            let memory_end = process_memory_mb(&mut lock(&self.system));
+            return (0.0, 0.0, 0, memory_end, memory_end);
+        }
+
+        let cpu_readings = lock(&self.cpu_readings);
+        let memory_readings = lock(&self.memory_readings);
+
+        let cpu_avg = if cpu_readings.is_empty() { 0.0 } else {
+            cpu_readings.iter().sum::<f64>() / cpu_readings.len() as f64
+        };
+        let cpu_peak = cpu_readings.iter().fold(0.0f64, |a, &b| a.max(b));
+        let cpu_samples = cpu_readings.len();
+
+        let memory_peak = memory_readings.iter().fold(0.0f64, |a, &b| a.max(b));
+        let memory_current = memory_readings.last().copied().unwrap_or(0.0);
+
+        (cpu_avg, cpu_peak, cpu_samples, memory_peak, memory_current)
+    }
+}
+
+/// Resident set size of the current process in MB, as sampled by
+/// `SystemMonitor`. Unlike whole-system used memory this is not affected by
+/// other processes on the machine.
+pub fn memory_usage_mb() -> f64 {
+    process_memory_mb(&mut System::new())
+}
+
+fn process_memory_mb(system: &mut System) -> f64 {
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return 0.0;
+    };
+    system.refresh_process(pid);
+    system.process(pid)
+        .map(|process| process.memory() as f64 / (1024.0 * 1024.0))
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Held by tests that measure process memory, so another test freeing
+    /// its buffers mid-measurement cannot shrink the RSS they read.
+    static MEMORY_TESTS: Mutex<()> = Mutex::new(());
+
+    /// Allocates and touches `mb` megabytes, like a prediction's buffers.
+    fn work(mb: usize) -> Vec<u8> {
+        let buffer = vec![1u8; mb * 1024 * 1024];
+        std::hint::black_box(buffer)
+    }
+
+    #[test]
+    fn process_memory_is_measured() {
+        assert!(memory_usage_mb() > 0.0);
+    }
+
+    #[test]
+    fn back_to_back_runs_never_report_a_negative_delta() {
+        let _guard = lock(&MEMORY_TESTS);
+        let monitor = SystemMonitor::new().with_sample_interval(Duration::from_millis(5));
+        let mut held = Vec::new();
+        for _ in 0..2 {
+            let memory_start = memory_usage_mb();
+            monitor.start_monitoring();
+            held.push(work(8));
+            let (_, _, samples, memory_peak, memory_end) = monitor.stop_monitoring();
+            assert!(samples > 0);
+            assert!(memory_end - memory_start >= 0.0, "delta {} MB", memory_end - memory_start);
+            assert!(memory_peak >= memory_end);
+        }
+    }
+
+    #[test]
+    fn a_disabled_monitor_still_reads_the_end_memory() {
+        let _guard = lock(&MEMORY_TESTS);
+        let monitor = SystemMonitor::new().with_monitoring(false);
+        let memory_start = memory_usage_mb();
+        monitor.start_monitoring();
+        let _held = work(8);
+        let (_, _, samples, memory_peak, memory_end) = monitor.stop_monitoring();
+        assert_eq!(samples, 0);
+        assert_eq!(memory_peak, memory_end);
+        assert!(memory_end - memory_start >= 0.0, "delta {} MB", memory_end - memory_start);
+    }
+}
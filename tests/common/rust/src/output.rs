@@ -0,0 +1,95 @@
+//! Checks applied to a model's output tensor before the harnesses read
+//! class scores out of it.
+
+use anyhow::{bail, Result};
+
+/// Largest output rank the harness flattens, e.g. `(N,)`, `(1, N)` or
+/// `(1, N, 1)`.
+pub const MAX_OUTPUT_RANK: usize = 3;
+
+/// Fails when an output of `shape` has no elements, e.g. `(1, 0)`, naming
+/// the model at `model_path`. A zero-rank shape is a scalar, not empty.
+pub fn check_nonempty_output(model_path: &str, shape: &[usize]) -> Result<()> {
+    if shape.iter().product::<usize>() == 0 {
+        bail!("model '{}' produced an empty output tensor (shape {:?})", model_path, shape);
+    }
+    Ok(())
+}
+
+/// Checks that an output of `shape` for a `rows`-row batch can be read as
+/// `rows` flat rows: at most `MAX_OUTPUT_RANK` dimensions, a leading batch
+/// dimension equal to `rows` when there is more than one dimension, and at
+/// most one trailing dimension larger than 1.
+pub fn check_output_shape(shape: &[usize], rows: usize) -> Result<()> {
+    if shape.len() > MAX_OUTPUT_RANK {
+        bail!(
+            "model output has unexpected rank {} (shape {:?}) - expected at most {} dimensions, e.g. (N,), (1, N) or (1, N, 1)",
+            shape.len(), shape, MAX_OUTPUT_RANK
+        );
+    }
+    if shape.len() > 1 && shape[0] != rows {
+        bail!(
+            "model output of shape {:?} has batch dimension {} but {} row(s) were run",
+            shape, shape[0], rows
+        );
+    }
+    if shape.len() > 1 && shape[1..].iter().filter(|&&dim| dim > 1).count() > 1 {
+        bail!(
+            "model output of shape {:?} has more than one non-singleton dimension per row - cannot flatten it into class scores",
+            shape
+        );
+    }
+    Ok(())
+}
+
+/// Fails on the first NaN or infinite output element, naming its flat index.
+pub fn check_finite_output(values: impl Iterator<Item = f32>) -> Result<()> {
+    for (index, value) in values.enumerate() {
+        if !value.is_finite() {
+            bail!("model output index {} is non-finite ({}) - refusing to report a label", index, value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_output_names_the_model_and_shape() {
+        let error = check_nonempty_output("stub.onnx", &[1, 0]).unwrap_err();
+        assert_eq!(error.to_string(), "model 'stub.onnx' produced an empty output tensor (shape [1, 0])");
+        assert!(check_nonempty_output("stub.onnx", &[0]).is_err());
+        assert!(check_nonempty_output("stub.onnx", &[1, 2]).is_ok());
+        assert!(check_nonempty_output("stub.onnx", &[]).is_ok());
+    }
+
+    #[test]
+    fn accepts_a_batched_row_and_a_flat_vector() {
+        assert!(check_output_shape(&[1, 2], 1).is_ok());
+        assert!(check_output_shape(&[2], 1).is_ok());
+        assert!(check_output_shape(&[1, 1], 1).is_ok());
+        assert!(check_output_shape(&[1, 4, 1], 1).is_ok());
+        assert!(check_output_shape(&[3, 2], 3).is_ok());
+    }
+
+    #[test]
+    fn rejects_an_unexpected_rank() {
+        let error = check_output_shape(&[1, 1, 2, 1], 1).unwrap_err();
+        assert!(error.to_string().contains("unexpected rank 4"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_a_mismatched_batch_or_a_second_class_axis() {
+        assert!(check_output_shape(&[2, 2], 1).is_err());
+        assert!(check_output_shape(&[1, 2, 3], 1).is_err());
+    }
+
+    #[test]
+    fn finite_check_names_the_first_bad_index() {
+        assert!(check_finite_output([0.2, 0.8].into_iter()).is_ok());
+        let error = check_finite_output([0.2, f32::NAN, f32::INFINITY].into_iter()).unwrap_err();
+        assert!(error.to_string().contains("index 1"), "{}", error);
+    }
+}
@@ -0,0 +1,87 @@
+//! Spread of the scores a run produces, to catch models that give every
+//! input the same output.
+
+/// Default output variance below which a run's predictions count as constant
+/// (a standard deviation of 0.01).
+pub const DEFAULT_MIN_OUTPUT_VARIANCE: f64 = 1e-4;
+
+/// Running mean and variance (Welford) of the probabilities produced during a
+/// run, used to catch models that score every input the same.
+#[derive(Debug, Default)]
+pub struct PredictionSpread {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl PredictionSpread {
+    pub fn add(&mut self, probability: f32) {
+        self.count += 1;
+        let delta = probability as f64 - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (probability as f64 - self.mean);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.count > 1 { self.m2 / self.count as f64 } else { 0.0 }
+    }
+
+    /// Whether at least two predictions were made and their variance is
+    /// below `threshold`.
+    pub fn is_degenerate(&self, threshold: f64) -> bool {
+        self.count >= 2 && self.variance() < threshold
+    }
+
+    /// Warns on stderr when the output variance is below `threshold`, so
+    /// the warning never mixes into JSON results on stdout.
+    pub fn warn_if_degenerate(&self, threshold: f64) {
+        if !self.is_degenerate(threshold) {
+            return;
+        }
+        tracing::warn!(
+            "⚠️ Degenerate predictions: output variance {:.2e} across {} predictions is below {:.2e}. Every input scores ~{:.4} - check for a wrong model, empty vocab or all-OOV inputs",
+            self.variance(), self.count, threshold, self.mean
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spread(probabilities: &[f32]) -> PredictionSpread {
+        let mut spread = PredictionSpread::default();
+        for &probability in probabilities {
+            spread.add(probability);
+        }
+        spread
+    }
+
+    #[test]
+    fn tracks_the_population_mean_and_variance() {
+        let spread = spread(&[0.2, 0.4, 0.6, 0.8]);
+        assert_eq!(spread.count(), 4);
+        assert!((spread.mean() - 0.5).abs() < 1e-6);
+        assert!((spread.variance() - 0.05).abs() < 1e-6);
+    }
+
+    #[test]
+    fn constant_scores_are_degenerate() {
+        assert!(spread(&[0.5, 0.5, 0.5001]).is_degenerate(DEFAULT_MIN_OUTPUT_VARIANCE));
+        assert!(!spread(&[0.1, 0.9, 0.5]).is_degenerate(DEFAULT_MIN_OUTPUT_VARIANCE));
+    }
+
+    #[test]
+    fn a_single_prediction_is_never_degenerate() {
+        assert!(!spread(&[]).is_degenerate(DEFAULT_MIN_OUTPUT_VARIANCE));
+        assert!(!spread(&[0.5]).is_degenerate(DEFAULT_MIN_OUTPUT_VARIANCE));
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::file_hash::ModelFileHashes;
+use crate::latency::LatencyStats;
+use crate::metrics::PerformanceMetrics;
+use crate::system_info::SystemInfo;
+
+/// Version of the `BenchmarkReport` JSON layout. Bump it whenever a field is
+/// renamed, removed or changes meaning, so tools comparing reports over time
+/// can tell formats apart.
+pub const BENCHMARK_REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Durable record of a `--benchmark` run, written as JSON by `--report`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub schema_version: u32,
+    pub harness: String,
+    pub system: SystemInfo,
+    pub iterations: usize,
+    pub warmup_runs: usize,
+    pub predictions: usize,
+    pub total_time_ms: f64,
+    pub throughput_per_sec: f64,
+    pub latency: LatencyStats,
+    /// Full performance block, for harnesses that measure it.
+    pub metrics: Option<PerformanceMetrics>,
+    /// SHA-256 of the model, vocab and scaler files the run used.
+    pub model_files: Option<ModelFileHashes>,
+}
+
+impl BenchmarkReport {
+    /// Writes the report as pretty-printed JSON to `path`, replacing any
+    /// existing file. Fails with an error naming the path when its directory
+    /// does not exist.
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() && !parent.is_dir() {
+                return Err(io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("cannot write report '{}': directory '{}' does not exist", path, parent.display()),
+                ));
+            }
+        }
+        let file = File::create(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("cannot write report '{}': {}", path, e)))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,248 @@
+//! ONNX Runtime session setup shared by the harnesses that run a model:
+//! execution provider and graph optimization level selection, and building
+//! the session from those settings. Compiled with the `session` feature, so
+//! harnesses without a session do not link ONNX Runtime.
+
+use anyhow::{bail, Context, Result};
+use ort::{Environment, ExecutionProvider, GraphOptimizationLevel, Session, SessionBuilder};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// ONNX Runtime session options chosen on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct SessionConfig {
+    pub no_spin: bool,
+    /// Available providers in priority order, as registered with the session.
+    pub providers: Vec<Provider>,
+    pub opt_level: OptLevel,
+    /// Intra-op thread count; 0 leaves the choice to ONNX Runtime.
+    pub intra_threads: i16,
+}
+
+impl SessionConfig {
+    /// Provider the session binds: the first available one in the chain.
+    pub fn provider(&self) -> Provider {
+        self.providers.first().copied().unwrap_or_default()
+    }
+}
+
+/// ONNX Runtime execution provider selected with `--provider`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Provider {
+    #[default]
+    Cpu,
+    Cuda,
+    /// Apple CoreML; only selectable on macOS.
+    Coreml,
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "cpu" => Ok(Provider::Cpu),
+            "cuda" => Ok(Provider::Cuda),
+            "coreml" => Ok(Provider::Coreml),
+            _ => Err(format!("unknown execution provider '{}' - expected 'cpu', 'cuda' or 'coreml'", value)),
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Provider {
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::Cpu => "cpu",
+            Provider::Cuda => "cuda",
+            Provider::Coreml => "coreml",
+        }
+    }
+
+    fn execution_provider(self) -> ExecutionProvider {
+        match self {
+            Provider::Cpu => ExecutionProvider::CPU(Default::default()),
+            Provider::Cuda => ExecutionProvider::CUDA(Default::default()),
+            Provider::Coreml => ExecutionProvider::CoreML(Default::default()),
+        }
+    }
+
+    /// Whether this build can register the provider and the linked ONNX
+    /// Runtime library ships it. CPU is always available and is answered
+    /// without calling into ONNX Runtime.
+    pub fn is_available(self) -> bool {
+        let compiled_in = match self {
+            Provider::Cpu => return true,
+            Provider::Cuda => cfg!(feature = "cuda"),
+            Provider::Coreml => cfg!(all(target_os = "macos", feature = "coreml")),
+        };
+        compiled_in && self.execution_provider().is_available()
+    }
+
+    /// Returns the provider sessions will run on: `self` when available,
+    /// otherwise CPU after a warning on stderr. CoreML is an error outside
+    /// macOS rather than a silent fallback.
+    pub fn resolve(self) -> Result<Provider> {
+        #[cfg(not(target_os = "macos"))]
+        if self == Provider::Coreml {
+            bail!("the coreml execution provider is only available on macOS (running on {})", std::env::consts::OS);
+        }
+        if self.is_available() {
+            return Ok(self);
+        }
+        tracing::warn!("⚠️ {} execution provider is not available - falling back to cpu", self.name());
+        Ok(Provider::Cpu)
+    }
+
+    /// Returns the `requested` providers that are available, in priority
+    /// order, skipping each unavailable one after a warning on stderr. When
+    /// none is available the chain is CPU alone. `--provider coreml` on its
+    /// own is an error outside macOS rather than a silent fallback.
+    pub fn resolve_chain(requested: &[Provider]) -> Result<Vec<Provider>> {
+        #[cfg(not(target_os = "macos"))]
+        if requested == [Provider::Coreml] {
+            bail!("the coreml execution provider is only available on macOS (running on {})", std::env::consts::OS);
+        }
+        let mut chain: Vec<Provider> = Vec::new();
+        for &provider in requested {
+            if chain.contains(&provider) {
+                continue;
+            }
+            if provider.is_available() {
+                chain.push(provider);
+            } else {
+                tracing::warn!("⚠️ {} execution provider is not available - skipping it", provider.name());
+            }
+        }
+        if chain.is_empty() {
+            tracing::warn!("⚠️ No requested execution provider is available - falling back to cpu");
+            chain.push(Provider::Cpu);
+        }
+        Ok(chain)
+    }
+}
+
+/// ONNX Runtime graph optimization level selected with `--opt-level`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OptLevel {
+    /// No graph optimizations.
+    Disable,
+    /// Semantics-preserving rewrites such as constant folding.
+    Basic,
+    /// Basic plus complex node fusions.
+    Extended,
+    /// Extended plus layout optimizations; ONNX Runtime's default.
+    #[default]
+    All,
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "disable" => Ok(OptLevel::Disable),
+            "basic" => Ok(OptLevel::Basic),
+            "extended" => Ok(OptLevel::Extended),
+            "all" => Ok(OptLevel::All),
+            _ => Err(format!(
+                "unknown optimization level '{}' - expected 'disable', 'basic', 'extended' or 'all'",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl OptLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            OptLevel::Disable => "disable",
+            OptLevel::Basic => "basic",
+            OptLevel::Extended => "extended",
+            OptLevel::All => "all",
+        }
+    }
+
+    pub fn graph_optimization_level(self) -> GraphOptimizationLevel {
+        match self {
+            OptLevel::Disable => GraphOptimizationLevel::Disable,
+            OptLevel::Basic => GraphOptimizationLevel::Level1,
+            OptLevel::Extended => GraphOptimizationLevel::Level2,
+            OptLevel::All => GraphOptimizationLevel::Level3,
+        }
+    }
+}
+
+/// Creates the ORT environment named `harness` and a session for
+/// `model_path` with `config`.
+pub fn build_session(harness: &str, model_path: &str, config: &SessionConfig) -> Result<Session> {
+    let mut environment_builder = Environment::builder()
+        .with_name(harness);
+    if config.no_spin {
+        // Spin control is only configurable on the global thread pool, so
+        // sessions opt out of their per-session pools below and the thread
+        // count is set on the global pool instead.
+        environment_builder = environment_builder.with_global_thread_pool(vec![
+            ("spin_control".to_string(), "0".to_string()),
+            ("intra_op_parallelism".to_string(), config.intra_threads.to_string()),
+        ]);
+    }
+    let environment = Arc::new(environment_builder.build()?);
+
+    let mut session_builder = SessionBuilder::new(&environment)?
+        .with_execution_providers(config.providers.iter().map(|provider| provider.execution_provider()).collect::<Vec<_>>())?
+        .with_optimization_level(config.opt_level.graph_optimization_level())?;
+    if config.no_spin {
+        session_builder = session_builder.with_disable_per_session_threads()?;
+    } else {
+        session_builder = session_builder.with_intra_threads(config.intra_threads)?;
+    }
+    session_builder
+        .with_model_from_file(model_path)
+        .with_context(|| format!("failed to build ONNX session for '{}'", model_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opt_level_strings_map_to_graph_optimization_levels() {
+        let cases = [
+            ("disable", OptLevel::Disable),
+            ("basic", OptLevel::Basic),
+            ("extended", OptLevel::Extended),
+            ("all", OptLevel::All),
+        ];
+        for (name, level) in cases {
+            assert_eq!(name.parse::<OptLevel>(), Ok(level));
+            assert_eq!(level.to_string(), name);
+        }
+        assert!(matches!(OptLevel::Disable.graph_optimization_level(), GraphOptimizationLevel::Disable));
+        assert!(matches!(OptLevel::Basic.graph_optimization_level(), GraphOptimizationLevel::Level1));
+        assert!(matches!(OptLevel::Extended.graph_optimization_level(), GraphOptimizationLevel::Level2));
+        assert!(matches!(OptLevel::All.graph_optimization_level(), GraphOptimizationLevel::Level3));
+        assert_eq!(OptLevel::default(), OptLevel::All);
+        assert!("level3".parse::<OptLevel>().is_err());
+    }
+
+    #[test]
+    fn provider_strings_round_trip() {
+        for provider in [Provider::Cpu, Provider::Cuda, Provider::Coreml] {
+            assert_eq!(provider.to_string().parse::<Provider>(), Ok(provider));
+        }
+        assert!("tensorrt".parse::<Provider>().is_err());
+    }
+}
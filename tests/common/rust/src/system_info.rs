@@ -0,0 +1,108 @@
+use serde::Serialize;
+use sysinfo::{CpuExt, System, SystemExt};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub platform: String,
+    pub architecture: String,
+    pub cpu_brand: String,
+    pub cpu_cores_physical: usize,
+    pub cpu_cores_logical: usize,
+    pub cpu_frequency_mhz: u64,
+    pub total_memory_gb: f64,
+    pub available_memory_gb: f64,
+    pub rust_version: String,
+    pub onnx_version: String,
+    pub compiler_version: String,
+    /// Whether ONNX Runtime intra-op threads spin-wait. `None` for harnesses
+    /// that do not run an ORT session, in which case the line is omitted.
+    pub intra_op_spinning: Option<bool>,
+    /// ONNX Runtime execution provider the session runs on. `None` for
+    /// harnesses without an ORT session.
+    pub execution_provider: Option<String>,
+    /// Number of ONNX Runtime intra-op threads selected with `--threads`.
+    /// `None` for harnesses that take no thread count.
+    pub intra_op_threads: Option<usize>,
+    /// ONNX Runtime graph optimization level selected with `--opt-level`.
+    /// `None` for harnesses without an ORT session.
+    pub graph_optimization_level: Option<String>,
+}
+
+impl SystemInfo {
+    pub fn collect() -> Self {
+        let mut system = System::new_all();
+        system.refresh_all();
+
+        let platform = format!("{} {}", std::env::consts::OS,
+                              system.kernel_version().unwrap_or_else(|| "Unknown".to_string()));
+        let architecture = std::env::consts::ARCH.to_string();
+
+        let cpu_brand = system.cpus().first()
+            .map(|cpu| cpu.brand().to_string())
+            .unwrap_or_else(|| "Unknown CPU".to_string());
+
+        let cpu_cores_physical = system.physical_core_count().unwrap_or(0);
+        let cpu_cores_logical = system.cpus().len();
+
+        let cpu_frequency_mhz = system.cpus().first()
+            .map(|cpu| cpu.frequency())
+            .unwrap_or(0);
+
+        let total_memory_gb = system.total_memory() as f64 / (1024.0 * 1024.0 * 1024.0);
+        let available_memory_gb = system.available_memory() as f64 / (1024.0 * 1024.0 * 1024.0);
+
+        let rust_version = format!("{} ({})",
+                                  env!("CARGO_PKG_VERSION"),
+                                  option_env!("RUSTC_VERSION").unwrap_or("unknown"));
+        let onnx_version = "1.16.3".to_string();
+        let compiler_version = format!("rustc {}",
+                                     option_env!("RUSTC_VERSION").unwrap_or("unknown"));
+
+        Self {
+            platform,
+            architecture,
+            cpu_brand,
+            cpu_cores_physical,
+            cpu_cores_logical,
+            cpu_frequency_mhz,
+            total_memory_gb,
+            available_memory_gb,
+            rust_version,
+            onnx_version,
+            compiler_version,
+            intra_op_spinning: None,
+            execution_provider: None,
+            intra_op_threads: None,
+            graph_optimization_level: None,
+        }
+    }
+
+    pub fn print(&self) {
+        println!("🖥️  SYSTEM INFORMATION:");
+        println!("   Platform: {}", self.platform);
+        println!("   Architecture: {}", self.architecture);
+        println!("   CPU: {}", self.cpu_brand);
+        println!("   CPU Cores: {} physical, {} logical", self.cpu_cores_physical, self.cpu_cores_logical);
+        if self.cpu_frequency_mhz > 0 {
+            println!("   CPU Frequency: {} MHz", self.cpu_frequency_mhz);
+        }
+        println!("   Total Memory: {:.2} GB", self.total_memory_gb);
+        println!("   Available Memory: {:.2} GB", self.available_memory_gb);
+        println!("   Rust Version: {}", self.rust_version);
+        println!("   ONNX Runtime: {}", self.onnx_version);
+        println!("   Compiler: {}", self.compiler_version);
+        if let Some(spinning) = self.intra_op_spinning {
+            println!("   Intra-op Spinning: {}", if spinning { "enabled" } else { "disabled" });
+        }
+        if let Some(provider) = &self.execution_provider {
+            println!("   Execution Provider: {}", provider);
+        }
+        if let Some(threads) = self.intra_op_threads {
+            println!("   Intra-op Threads: {}", threads);
+        }
+        if let Some(level) = &self.graph_optimization_level {
+            println!("   Graph Optimization: {}", level);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+/// Keys a vocab.json may hold the term-to-index map under: the harnesses'
+/// own `vocab`, and `vocabulary` or sklearn's fitted-attribute name
+/// `vocabulary_` from a serialized `TfidfVectorizer`.
+pub const VOCABULARY_KEYS: [&str; 3] = ["vocab", "vocabulary", "vocabulary_"];
+
+/// Keys a vocab.json may hold the IDF weights under, in the same order.
+pub const IDF_KEYS: [&str; 2] = ["idf", "idf_"];
+
+/// The term-to-index map of a vocab.json object, under the first of
+/// `VOCABULARY_KEYS` whose value is an object. A plain word-to-index map
+/// that happens to contain one of the keys as a word is not mistaken for
+/// it, since a word's index is a number.
+pub fn vocabulary_object(data: &Value) -> Option<&Map<String, Value>> {
+    VOCABULARY_KEYS.iter().find_map(|key| data.get(key)?.as_object())
+}
+
+/// The first of `IDF_KEYS` present in a vocab.json object, else `idf` so
+/// errors name the harnesses' own key.
+pub fn idf_key(data: &Value) -> &'static str {
+    IDF_KEYS.into_iter().find(|key| data.get(key).is_some()).unwrap_or(IDF_KEYS[0])
+}
+
+/// Normalization applied to a TF-IDF vector after IDF weighting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Norm {
+    /// Leave the weights as they are.
+    #[default]
+    None,
+    /// Scale the vector to unit Euclidean length, like sklearn's `norm="l2"`.
+    L2,
+}
+
+/// TF-IDF vectorizer over a fixed vocabulary and IDF table, as exported from
+/// the training pipeline. The options select which variant a harness
+/// reproduces:
+///
+/// - `sublinear_tf` replaces a term count `c` with `1 + ln(c)`.
+/// - `relative_tf` divides term frequencies by the total number of tokens,
+///   including tokens outside the vocabulary.
+/// - `norm` is applied to the weighted vector.
+///
+/// Vocabulary indices at or beyond `idf.len()` are ignored;
+/// `out_of_range_terms` lists them so a loader can refuse such a vocab.
+#[derive(Debug, Clone)]
+pub struct TfidfVectorizer {
+    pub vocab: HashMap<String, usize>,
+    pub idf: Vec<f32>,
+    pub norm: Norm,
+    pub sublinear_tf: bool,
+    pub relative_tf: bool,
+}
+
+impl TfidfVectorizer {
+    /// Vectorizer with raw counts and no normalization.
+    pub fn new(vocab: HashMap<String, usize>, idf: Vec<f32>) -> Self {
+        Self {
+            vocab,
+            idf,
+            norm: Norm::None,
+            sublinear_tf: false,
+            relative_tf: false,
+        }
+    }
+
+    /// Length of the vectors produced by `transform`.
+    pub fn feature_count(&self) -> usize {
+        self.idf.len()
+    }
+
+    /// Terms whose vocabulary index is at or beyond `feature_count()`, with
+    /// their indices, in index order. `term_weights` would silently skip
+    /// them, so a non-empty result means the vocab and IDF table disagree.
+    pub fn out_of_range_terms(&self) -> Vec<(&str, usize)> {
+        let mut terms: Vec<(&str, usize)> = self.vocab.iter()
+            .filter(|&(_, &idx)| idx >= self.feature_count())
+            .map(|(term, &idx)| (term.as_str(), idx))
+            .collect();
+        terms.sort_unstable_by_key(|&(term, idx)| (idx, term));
+        terms
+    }
+
+    /// Lowercases and whitespace-splits `text`, and returns its dense TF-IDF
+    /// vector.
+    pub fn transform(&self, text: &str) -> Vec<f32> {
+        let text_lower = text.to_lowercase();
+        let tokens: Vec<&str> = text_lower.split_whitespace().collect();
+        let mut vector = vec![0.0; self.feature_count()];
+        for (idx, weight) in self.term_weights(&tokens) {
+            vector[idx] = weight;
+        }
+        vector
+    }
+
+    /// Non-zero TF-IDF weights of already tokenized input as
+    /// `(feature index, weight)` pairs in index order. Harnesses with their
+    /// own tokenization, or that only touch the features an input hits,
+    /// use this instead of `transform`.
+    pub fn term_weights<S: AsRef<str>>(&self, tokens: &[S]) -> Vec<(usize, f32)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for token in tokens {
+            *counts.entry(token.as_ref()).or_insert(0) += 1;
+        }
+
+        let mut weights: Vec<(usize, f32)> = counts.into_iter()
+            .filter_map(|(term, count)| {
+                let idx = *self.vocab.get(term)?;
+                let idf = *self.idf.get(idx)?;
+                let mut tf = if self.sublinear_tf { 1.0 + (count as f32).ln() } else { count as f32 };
+                if self.relative_tf {
+                    tf /= tokens.len() as f32;
+                }
+                Some((idx, tf * idf))
+            })
+            .collect();
+        weights.sort_unstable_by_key(|&(idx, _)| idx);
+
+        if self.norm == Norm::L2 {
+            let length = weights.iter().map(|(_, weight)| weight * weight).sum::<f32>().sqrt();
+            if length > 0.0 {
+                for (_, weight) in &mut weights {
+                    *weight /= length;
+                }
+            }
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sklearn_attribute_names_are_found() {
+        let data = serde_json::json!({
+            "vocabulary_": {"good": 0, "movie": 1},
+            "idf_": [1.5, 2.0],
+            "max_features": 5000,
+        });
+        let vocabulary = vocabulary_object(&data).unwrap();
+        assert_eq!(vocabulary.len(), 2);
+        assert_eq!(vocabulary["movie"], 1);
+        assert_eq!(idf_key(&data), "idf_");
+        assert_eq!(data[idf_key(&data)][1], 2.0);
+    }
+
+    #[test]
+    fn the_harness_layout_wins_over_sklearn_names() {
+        let data = serde_json::json!({"vocab": {"good": 0}, "vocabulary_": {"bad": 0}, "idf": [1.0]});
+        assert!(vocabulary_object(&data).unwrap().contains_key("good"));
+        assert_eq!(idf_key(&data), "idf");
+    }
+
+    #[test]
+    fn a_plain_word_map_is_not_mistaken_for_a_wrapper() {
+        let data = serde_json::json!({"vocabulary": 0, "vocab": 1, "movie": 2});
+        assert!(vocabulary_object(&data).is_none());
+        // With neither key present, errors name the harnesses' own key
+        assert_eq!(idf_key(&data), "idf");
+    }
+
+    /// Vocabulary good/movie/bad with IDF 2, 1 and 3.
+    fn vectorizer() -> TfidfVectorizer {
+        let vocab = [("good", 0), ("movie", 1), ("bad", 2)]
+            .into_iter()
+            .map(|(term, idx)| (term.to_string(), idx))
+            .collect();
+        TfidfVectorizer::new(vocab, vec![2.0, 1.0, 3.0])
+    }
+
+    /// "good" four times, "movie" once and one out-of-vocabulary token.
+    const TEXT: &str = "Good good GOOD good movie unseen";
+
+    fn assert_vector(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn raw_counts_are_weighted_by_idf() {
+        assert_vector(&vectorizer().transform(TEXT), &[8.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn sublinear_tf_takes_one_plus_the_log_count() {
+        let vectorizer = TfidfVectorizer { sublinear_tf: true, ..vectorizer() };
+        // (1 + ln 4) * 2 and (1 + ln 1) * 1
+        assert_vector(&vectorizer.transform(TEXT), &[4.772_589, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn relative_tf_divides_by_every_token_including_unknown_ones() {
+        let vectorizer = TfidfVectorizer { relative_tf: true, ..vectorizer() };
+        // 4/6 * 2 and 1/6 * 1
+        assert_vector(&vectorizer.transform(TEXT), &[1.333_333_3, 0.166_666_67, 0.0]);
+    }
+
+    #[test]
+    fn l2_norm_scales_to_unit_length() {
+        let vectorizer = TfidfVectorizer { norm: Norm::L2, ..vectorizer() };
+        // [8, 1] / sqrt(65)
+        assert_vector(&vectorizer.transform(TEXT), &[0.992_277_9, 0.124_034_73, 0.0]);
+
+        let vectorizer = TfidfVectorizer { sublinear_tf: true, norm: Norm::L2, ..vectorizer };
+        assert_vector(&vectorizer.transform(TEXT), &[0.978_745_9, 0.205_076_53, 0.0]);
+    }
+
+    #[test]
+    fn l2_norm_leaves_an_all_zero_vector_alone() {
+        let vectorizer = TfidfVectorizer { norm: Norm::L2, ..vectorizer() };
+        assert_vector(&vectorizer.transform("unseen words only"), &[0.0, 0.0, 0.0]);
+    }
+}
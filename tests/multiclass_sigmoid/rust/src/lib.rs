@@ -0,0 +1,616 @@
+use std::env;
+use std::io::Read;
+use std::time::Instant;
+use std::path::Path;
+use colored::Colorize;
+use ndarray::Array2;
+use whitelightning_common::{
+    init_logging, print_log_summary, probability_bar, terminal_width, BenchmarkReport, ClassificationResult, Classifier,
+    LatencyHistogram, LatencyStats, LogFormat, ModelFileHashes, SystemInfo, BENCHMARK_REPORT_SCHEMA_VERSION,
+    MODEL_FILE_NAMES,
+};
+
+const EMOTIONS: [&str; 4] = ["fear", "happy", "love", "sadness"];
+
+/// Probability above which an emotion is reported as an active label.
+const DEFAULT_LABEL_THRESHOLD: f32 = 0.5;
+
+/// Untimed warmup passes before `--benchmark` starts measuring.
+const DEFAULT_WARMUP_RUNS: usize = 5;
+
+/// Wall-clock time of one analysis, split into its phases, in milliseconds.
+#[derive(Debug, Clone, Copy, Default)]
+struct TimingMetrics {
+    total_ms: f64,
+    preprocessing_ms: f64,
+    inference_ms: f64,
+    postprocessing_ms: f64,
+}
+
+impl TimingMetrics {
+    /// Prints the `PERFORMANCE SUMMARY` block in the multiclass classifier's layout.
+    fn print(&self) {
+        println!("📈 PERFORMANCE SUMMARY:");
+        println!("   Total Processing Time: {:.1}ms", self.total_ms);
+        println!("   ┣━ Preprocessing: {:.1}ms", self.preprocessing_ms);
+        println!("   ┣━ Model Inference: {:.1}ms", self.inference_ms);
+        println!("   ┗━ Postprocessing: {:.1}ms", self.postprocessing_ms);
+        println!();
+    }
+}
+
+/// How much is printed besides the analysis results and performance summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    /// Results only, without the banner and system information (`--quiet`).
+    Quiet,
+    /// Banner, system information and results (the default).
+    Normal,
+    /// Also the component loading and feature shape lines (`--verbose`).
+    Verbose,
+}
+
+/// Runs the multiclass sigmoid harness with command-line `args`, where the
+/// first item is the program name as in `std::env::args()`.
+pub fn run<I: IntoIterator<Item = String>>(args: I) -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = args.into_iter().skip(1);
+    let mut positional: Vec<String> = Vec::new();
+    let mut output_tensor_file: Option<String> = None;
+    let mut log_summary = false;
+    let mut json = false;
+    let mut read_stdin = false;
+    let mut benchmark: Option<usize> = None;
+    let mut warmup: Option<usize> = None;
+    let mut report_path: Option<String> = None;
+    let mut histogram_path: Option<String> = None;
+    let mut threads: Option<usize> = None;
+    let mut verbosity = Verbosity::Normal;
+    let mut label_threshold = DEFAULT_LABEL_THRESHOLD;
+    let mut bars = false;
+    let mut log_format = LogFormat::Pretty;
+    let mut model_path = String::from("model.onnx");
+    let mut vocab_path = String::from("vocab.json");
+    let mut scaler_path = String::from("scaler.json");
+    while let Some(arg) = args.next() {
+        if arg == "--output-tensor-file" {
+            output_tensor_file = Some(args.next().ok_or("--output-tensor-file requires a path")?);
+        } else if arg == "--log-summary" {
+            log_summary = true;
+        } else if arg == "--json" {
+            json = true;
+        } else if arg == "--stdin" {
+            read_stdin = true;
+        } else if arg == "--model" {
+            model_path = args.next().ok_or("--model requires a path")?;
+        } else if arg == "--vocab" {
+            vocab_path = args.next().ok_or("--vocab requires a path")?;
+        } else if arg == "--scaler" {
+            scaler_path = args.next().ok_or("--scaler requires a path")?;
+        } else if arg == "--benchmark" {
+            let iterations = args.next().ok_or("--benchmark requires an iteration count")?;
+            benchmark = Some(iterations.parse().map_err(|_| "--benchmark requires a whole number of iterations")?);
+        } else if arg == "--report" {
+            report_path = Some(args.next().ok_or("--report requires a path")?);
+        } else if arg == "--histogram" {
+            histogram_path = Some(args.next().ok_or("--histogram requires a path, or - to print it")?);
+        } else if arg == "--warmup" {
+            let runs = args.next().ok_or("--warmup requires a run count")?;
+            warmup = Some(runs.parse().map_err(|_| "--warmup requires a whole number of runs")?);
+        } else if arg == "--quiet" || arg == "--verbose" {
+            if verbosity != Verbosity::Normal {
+                return Err("--quiet and --verbose can each be given once and not together".into());
+            }
+            verbosity = if arg == "--quiet" { Verbosity::Quiet } else { Verbosity::Verbose };
+        } else if arg == "--threads" {
+            let count = args.next().ok_or("--threads requires a thread count")?;
+            threads = Some(count.parse().map_err(|_| "--threads requires a whole number of threads")?);
+        } else if arg == "--bars" {
+            bars = true;
+        } else if arg == "--log-format" {
+            log_format = args.next().ok_or("--log-format requires pretty or json")?.parse()?;
+        } else if arg == "--label-threshold" {
+            let threshold = args.next().ok_or("--label-threshold requires a probability")?;
+            label_threshold = threshold.parse().map_err(|_| "--label-threshold requires a number")?;
+            if !(0.0..=1.0).contains(&label_threshold) {
+                return Err("--label-threshold must be between 0 and 1".into());
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+    init_logging(log_format);
+    if benchmark.is_some() && (read_stdin || output_tensor_file.is_some()) {
+        return Err("--benchmark cannot be combined with --stdin or --output-tensor-file".into());
+    }
+    if json && (benchmark.is_some() || log_summary) {
+        return Err("--json cannot be combined with --benchmark or --log-summary".into());
+    }
+    if (warmup.is_some() || report_path.is_some() || histogram_path.is_some()) && benchmark.is_none() {
+        return Err("--warmup, --report and --histogram require --benchmark".into());
+    }
+    // Default to the logical CPU count, like the ONNX Runtime harnesses.
+    let threads = threads.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    if threads == 0 {
+        return Err("--threads must be at least 1".into());
+    }
+    if read_stdin {
+        if !positional.is_empty() {
+            return Err("--stdin cannot be combined with a text argument".into());
+        }
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        positional.push(text.trim_end_matches(['\n', '\r']).to_string());
+    }
+    if positional.first().is_some_and(|text| text.trim().is_empty()) {
+        return Err("empty input: the text to classify is empty or whitespace-only".into());
+    }
+    if json && positional.is_empty() {
+        return Err("--json requires a text to classify".into());
+    }
+    // JSON mode prints nothing but the result line
+    if json {
+        verbosity = Verbosity::Quiet;
+    }
+    let test_text = if let Some(text) = positional.first() {
+        text
+    } else {
+        "I'm about to give birth, and I'm terrified. What if something goes wrong? What if I can't handle the pain? Received an unexpected compliment at work today. Small moments of happiness can make a big difference."
+    };
+    
+    if verbosity >= Verbosity::Normal {
+        println!("🤖 ONNX MULTICLASS SIGMOID CLASSIFIER - RUST IMPLEMENTATION");
+        println!("{}", "=".repeat(62));
+        if benchmark.is_none() {
+            println!("🔄 Processing: {}", test_text);
+        }
+        println!();
+    }
+    
+    // System information
+    let mut system_info = SystemInfo::collect();
+    system_info.intra_op_threads = Some(threads);
+    if verbosity >= Verbosity::Normal {
+        system_info.print();
+    }
+    
+    let missing_files: Vec<&str> = [&model_path, &vocab_path, &scaler_path]
+        .into_iter()
+        .filter(|path| !Path::new(path).exists())
+        .map(String::as_str)
+        .collect();
+    if json && !missing_files.is_empty() {
+        return Err(format!("model files not found: {}", missing_files.join(", ")).into());
+    }
+
+    // Check if running in CI environment without model files
+    if (env::var("CI").is_ok() || env::var("GITHUB_ACTIONS").is_ok()) && !Path::new(&model_path).exists() {
+        println!("⚠️ Model files not found in CI environment - exiting safely");
+        println!("✅ Rust implementation compiled and started successfully");
+        println!("🏗️ Build verification completed");
+        return Ok(());
+    }
+    
+    let total_start = Instant::now();
+    
+    // Load components
+    if verbosity == Verbosity::Verbose {
+        println!("🔧 Loading components...");
+        println!("✅ ONNX model loaded (demo mode)");
+    }
+    
+    // Check if model files exist
+    if !missing_files.is_empty() {
+        println!("⚠️ Model files not found: {} - using simplified demo mode", missing_files.join(", "));
+        println!("✅ Rust implementation compiled and started successfully");
+        println!("🏗️ Build verification completed");
+        return Ok(());
+    }
+    
+    if verbosity == Verbosity::Verbose {
+        println!("✅ Components loaded");
+        println!();
+    }
+
+    // Hashed at load time so a --report can be tied to the exact files used
+    let model_files = ModelFileHashes::compute(&model_path, &vocab_path, &scaler_path)?;
+    if verbosity >= Verbosity::Normal {
+        model_files.print();
+    }
+
+    if let Some(iterations) = benchmark {
+        let options = BenchmarkOptions {
+            iterations,
+            warmup: warmup.unwrap_or(DEFAULT_WARMUP_RUNS),
+            log_summary_model: log_summary.then_some(model_path.as_str()),
+            report_path: report_path.as_deref(),
+            histogram_path: histogram_path.as_deref(),
+            model_files,
+        };
+        return run_benchmark(&options, system_info);
+    }
+    
+    if verbosity == Verbosity::Verbose {
+        println!("📊 TF-IDF shape: [1, 5000]");
+        println!();
+    }
+    
+    // Simulate emotion analysis
+    let analysis = analyze_emotions(test_text, label_threshold);
+    if json {
+        let mut timing = analysis.timing;
+        timing.total_ms = total_start.elapsed().as_secs_f64() * 1000.0;
+        println!("{}", analysis.to_json(&timing));
+        return Ok(());
+    }
+    analysis.print(test_text, label_threshold, bars);
+    let EmotionAnalysis { probabilities, mut timing, .. } = analysis;
+
+    if let Some(path) = &output_tensor_file {
+        let tensor = Array2::from_shape_vec((1, probabilities.len()), probabilities)?;
+        ndarray_npy::write_npy(path, &tensor)?;
+        println!("💾 Output tensor written to {} (shape {:?}, dtype float32)", path, tensor.shape());
+        println!();
+    }
+    
+    // Performance metrics
+    let total_time = total_start.elapsed();
+    let total_ms = total_time.as_millis();
+    
+    // Throughput, from the fractional time: whole milliseconds are often 0
+    let elapsed_ms = total_time.as_secs_f64() * 1000.0;
+    timing.total_ms = elapsed_ms;
+    timing.print();
+    
+    let throughput = if elapsed_ms > 0.0 { 1000.0 / elapsed_ms } else { 0.0 };
+    println!("🚀 THROUGHPUT:");
+    println!("   Texts per second: {:.1} (extrapolated from one text, not sustained)", throughput);
+    println!();
+    
+    // Performance rating
+    let rating = if total_ms < 50 {
+        "🚀 EXCELLENT"
+    } else if total_ms < 100 {
+        "✅ GOOD"
+    } else if total_ms < 500 {
+        "⚠️ ACCEPTABLE"
+    } else {
+        "🐌 SLOW"
+    };
+    
+    println!("🎯 PERFORMANCE RATING: {}", rating);
+    println!("   ({}ms total - Target: <100ms)", total_ms);
+
+    if log_summary {
+        print_log_summary("multiclass_sigmoid", &model_path, &[elapsed_ms], throughput, None)?;
+    }
+    
+    Ok(())
+}
+
+/// Settings of a `--benchmark` run.
+struct BenchmarkOptions<'a> {
+    iterations: usize,
+    warmup: usize,
+    /// Model path to hash for `--log-summary`.
+    log_summary_model: Option<&'a str>,
+    /// Where `--report` writes the JSON report.
+    report_path: Option<&'a str>,
+    /// Where `--histogram` writes the latency histogram; `-` prints it.
+    histogram_path: Option<&'a str>,
+    /// Hashes of the loaded files, included in the report.
+    model_files: ModelFileHashes,
+}
+
+/// Runs `warmup` untimed passes and then `iterations` timed passes of the
+/// emotion scoring over a fixed set of texts, and prints the latency stats.
+fn run_benchmark(options: &BenchmarkOptions, system_info: SystemInfo) -> Result<(), Box<dyn std::error::Error>> {
+    let BenchmarkOptions { iterations, warmup, .. } = *options;
+    let test_texts = [
+        "I'm about to give birth, and I'm terrified. What if something goes wrong?",
+        "Received an unexpected compliment at work today. Small moments of happiness can make a big difference.",
+        "I love spending quiet evenings with my family",
+        "The news left me feeling sad and empty for the rest of the day",
+    ];
+
+    println!("🚀 Running Rust Multiclass Sigmoid Benchmark");
+    println!("📊 Iterations: {}", iterations);
+    println!();
+
+    println!("🔥 Warming up model ({} runs)...", warmup);
+    for _ in 0..warmup {
+        for text in &test_texts {
+            emotion_probabilities(text);
+        }
+    }
+    println!();
+
+    println!("📊 Running benchmark...");
+    let mut latencies = Vec::with_capacity(iterations * test_texts.len());
+    let run_start = Instant::now();
+    for _ in 0..iterations {
+        for text in &test_texts {
+            let predict_start = Instant::now();
+            std::hint::black_box(emotion_probabilities(text));
+            latencies.push(predict_start.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+    let total_ms = run_start.elapsed().as_secs_f64() * 1000.0;
+    let throughput = latencies.len() as f64 / (total_ms / 1000.0);
+    println!();
+
+    println!("📈 PERFORMANCE SUMMARY:");
+    println!("   Total Processing Time: {:.2}ms", total_ms);
+    println!("   Total predictions: {}", latencies.len());
+    println!();
+
+    println!("🚀 THROUGHPUT:");
+    println!("   Texts per second: {:.1}", throughput);
+    println!();
+
+    let latency_stats = LatencyStats::from_latencies(&latencies);
+    latency_stats.print();
+    if let Some(path) = options.histogram_path {
+        let histogram = LatencyHistogram::from_latencies(&latencies);
+        if path == "-" {
+            histogram.print();
+        } else {
+            histogram.write(path).map_err(|e| e.to_string())?;
+            println!("📊 Latency histogram written to {}", path);
+        }
+    }
+
+    if let Some(model_path) = options.log_summary_model {
+        print_log_summary("multiclass_sigmoid", model_path, &latencies, throughput, None)?;
+    }
+    if let Some(path) = options.report_path {
+        let report = BenchmarkReport {
+            schema_version: BENCHMARK_REPORT_SCHEMA_VERSION,
+            harness: "multiclass_sigmoid".to_string(),
+            system: system_info,
+            iterations,
+            warmup_runs: warmup,
+            predictions: latencies.len(),
+            total_time_ms: total_ms,
+            throughput_per_sec: throughput,
+            latency: latency_stats,
+            metrics: None,
+            model_files: Some(options.model_files.clone()),
+        };
+        report.write(path).map_err(|e| e.to_string())?;
+        println!("📝 Benchmark report written to {}", path);
+    }
+
+    Ok(())
+}
+
+/// One scored text: its per-emotion probabilities, the emotions above the
+/// label threshold and the time spent in each phase.
+struct EmotionAnalysis {
+    probabilities: Vec<f32>,
+    dominant_idx: usize,
+    /// Indices and probabilities of the active labels, most likely first.
+    active: Vec<(usize, f32)>,
+    /// Phase times; `total_ms` is left for the caller to fill in.
+    timing: TimingMetrics,
+}
+
+/// Scores `text`, marking every emotion above `label_threshold` as an
+/// active label, and times each phase.
+fn analyze_emotions(text: &str, label_threshold: f32) -> EmotionAnalysis {
+    let mut timing = TimingMetrics::default();
+    let predict_span = tracing::debug_span!("predict").entered();
+
+    let preprocess_start = Instant::now();
+    let text_lower = tracing::debug_span!("preprocess").in_scope(|| preprocess_text(text));
+    timing.preprocessing_ms = preprocess_start.elapsed().as_secs_f64() * 1000.0;
+
+    let inference_start = Instant::now();
+    let probabilities = tracing::debug_span!("inference").in_scope(|| run_inference(text, &text_lower));
+    timing.inference_ms = inference_start.elapsed().as_secs_f64() * 1000.0;
+
+    let postprocess_start = Instant::now();
+    let (dominant_idx, active) = tracing::debug_span!("postprocess").in_scope(|| {
+        let (dominant_idx, _) = dominant_emotion(&probabilities);
+        (dominant_idx, active_labels(&probabilities, label_threshold))
+    });
+    timing.postprocessing_ms = postprocess_start.elapsed().as_secs_f64() * 1000.0;
+    tracing::debug!(
+        preprocessing_ms = timing.preprocessing_ms,
+        inference_ms = timing.inference_ms,
+        postprocessing_ms = timing.postprocessing_ms,
+        label = EMOTIONS[dominant_idx],
+        "prediction timed"
+    );
+    drop(predict_span);
+
+    EmotionAnalysis { probabilities, dominant_idx, active, timing }
+}
+
+impl EmotionAnalysis {
+    /// Prints the results, including the active labels above
+    /// `label_threshold` and, with `bars`, a bar per emotion.
+    fn print(&self, text: &str, label_threshold: f32, bars: bool) {
+        println!("📊 EMOTION ANALYSIS RESULTS:");
+        // Indent, the longest emotion name, ": ", "0.000" and a space come before the bar
+        let bar_width = terminal_width().saturating_sub(3 + 7 + 2 + 5 + 1 + 1).max(10);
+        for (i, (emotion, &prob)) in EMOTIONS.iter().zip(&self.probabilities).enumerate() {
+            if !bars {
+                println!("   {}: {:.3}", emotion, prob);
+                continue;
+            }
+            let line = format!("   {:<7}: {:.3} {}", emotion, prob, probability_bar(prob, bar_width));
+            if i == self.dominant_idx {
+                println!("{}", line.green().bold());
+            } else {
+                println!("{}", line);
+            }
+        }
+
+        println!("   🏆 Dominant Emotion: {} ({:.3})", EMOTIONS[self.dominant_idx], self.probabilities[self.dominant_idx]);
+        let active: Vec<String> = self.active.iter()
+            .map(|&(i, prob)| format!("{} ({:.3})", EMOTIONS[i], prob))
+            .collect();
+        println!("   🏷️  Active Labels (> {}): {}", label_threshold,
+                 if active.is_empty() { "none".to_string() } else { active.join(", ") });
+        println!("   📝 Input Text: \"{}\"", text);
+        println!();
+    }
+
+    /// The `--json` result: every emotion's probability in `EMOTIONS`
+    /// order, the active labels most likely first, and `timing`.
+    fn to_json(&self, timing: &TimingMetrics) -> serde_json::Value {
+        let label = |i: usize, probability: f32| serde_json::json!({ "label": EMOTIONS[i], "probability": probability });
+        serde_json::json!({
+            "probabilities": self.probabilities.iter().enumerate().map(|(i, &p)| label(i, p)).collect::<Vec<_>>(),
+            "active_labels": self.active.iter().map(|&(i, p)| label(i, p)).collect::<Vec<_>>(),
+            "timing": {
+                "total_ms": timing.total_ms,
+                "preprocessing_ms": timing.preprocessing_ms,
+                "inference_ms": timing.inference_ms,
+                "postprocessing_ms": timing.postprocessing_ms,
+            },
+        })
+    }
+}
+
+/// Per-emotion probabilities for `text`, in `EMOTIONS` order.
+fn emotion_probabilities(text: &str) -> Vec<f32> {
+    run_inference(text, &preprocess_text(text))
+}
+
+/// The demo's keyword scorer behind the `Classifier` trait. It reads no model
+/// files yet, but `classifier_from_dir` still requires them, as `run` does.
+struct SigmoidClassifier;
+
+impl Classifier for SigmoidClassifier {
+    fn classify(&self, text: &str) -> anyhow::Result<ClassificationResult> {
+        if text.trim().is_empty() {
+            anyhow::bail!("empty input: the text to classify is empty or whitespace-only");
+        }
+        Ok(ClassificationResult::Labels(
+            EMOTIONS.iter().map(|emotion| emotion.to_string()).zip(emotion_probabilities(text)).collect(),
+        ))
+    }
+}
+
+/// Loads the sigmoid classifier for the `MODEL_FILE_NAMES` files in `dir`,
+/// failing when any of them is missing.
+pub fn classifier_from_dir<P: AsRef<Path>>(dir: P) -> anyhow::Result<Box<dyn Classifier>> {
+    let missing_files: Vec<String> = MODEL_FILE_NAMES.iter()
+        .map(|name| dir.as_ref().join(name))
+        .filter(|path| !path.exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    if !missing_files.is_empty() {
+        anyhow::bail!("model files not found: {}", missing_files.join(", "));
+    }
+    Ok(Box::new(SigmoidClassifier))
+}
+
+/// Normalizes `text` for keyword matching.
+fn preprocess_text(text: &str) -> String {
+    text.to_lowercase()
+}
+
+/// Index and probability of the most likely emotion.
+fn dominant_emotion(probabilities: &[f32]) -> (usize, f32) {
+    let mut max_prob = 0.0f32;
+    let mut dominant_idx = 0;
+    for (i, &prob) in probabilities.iter().enumerate() {
+        if prob > max_prob {
+            max_prob = prob;
+            dominant_idx = i;
+        }
+    }
+    (dominant_idx, max_prob)
+}
+
+/// Indices and probabilities of the emotions above `threshold`, most
+/// likely first. Sigmoid outputs are independent, so any number can be active.
+fn active_labels(probabilities: &[f32], threshold: f32) -> Vec<(usize, f32)> {
+    let mut active: Vec<(usize, f32)> = probabilities.iter().copied()
+        .enumerate()
+        .filter(|&(_, prob)| prob > threshold)
+        .collect();
+    active.sort_by(|a, b| b.1.total_cmp(&a.1));
+    active
+}
+
+/// Scores the lowercased `text_lower`; the original `text` seeds the
+/// demo's pseudo-random baseline.
+fn run_inference(text: &str, text_lower: &str) -> Vec<f32> {
+    // Simple emotion detection based on keywords (simplified demo)
+    // Classes: fear, happy, love, sadness
+    let mut probabilities = vec![0.1f32; EMOTIONS.len()];
+    
+    if text_lower.contains("fear") || text_lower.contains("terrified") || text_lower.contains("scared") {
+        probabilities[0] = 0.9;
+    }
+    
+    if text_lower.contains("happy") || text_lower.contains("joy") || text_lower.contains("happiness") {
+        probabilities[1] = 0.8;
+    }
+    
+    if text_lower.contains("love") || text_lower.contains("romantic") {
+        probabilities[2] = 0.7;
+    }
+    
+    if text_lower.contains("sad") || text_lower.contains("sadness") || text_lower.contains("sorrow") {
+        probabilities[3] = 0.6;
+    }
+    
+    // Add some randomness for demonstration
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let seed = hasher.finish();
+    
+    for (i, prob) in probabilities.iter_mut().enumerate() {
+        if *prob <= 0.1 {
+            *prob = 0.1 + ((seed.wrapping_add(i as u64) % 100) as f32) / 1000.0;
+        }
+    }
+
+    probabilities
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lists_probabilities_active_labels_and_timing() {
+        let analysis = analyze_emotions("I am terrified and sad", DEFAULT_LABEL_THRESHOLD);
+        let timing = TimingMetrics { total_ms: 1.5, ..analysis.timing };
+        let json = analysis.to_json(&timing);
+
+        let probabilities = json["probabilities"].as_array().unwrap();
+        assert_eq!(probabilities.len(), EMOTIONS.len());
+        assert_eq!(probabilities[0]["label"], "fear");
+        let active: Vec<&str> = json["active_labels"].as_array().unwrap().iter()
+            .map(|label| label["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(active, ["fear", "sadness"]);
+        assert_eq!(json["timing"]["total_ms"], 1.5);
+        assert!(json["timing"]["inference_ms"].is_number());
+    }
+
+    #[test]
+    fn json_needs_a_text_and_no_benchmark() {
+        let run_with = |args: &[&str]| {
+            run(std::iter::once("multiclass_sigmoid_test").chain(args.iter().copied()).map(str::to_string))
+        };
+        assert!(run_with(&["--json"]).unwrap_err().to_string().contains("requires a text"));
+        assert!(run_with(&["--json", "--benchmark", "5"]).is_err());
+    }
+
+    #[test]
+    fn empty_or_whitespace_text_is_rejected_before_loading_the_model() {
+        for text in ["", "   "] {
+            let error = run(["multiclass_sigmoid_test".to_string(), text.to_string()]).unwrap_err();
+            assert!(error.to_string().contains("empty input"), "{:?}: {}", text, error);
+        }
+    }
+}
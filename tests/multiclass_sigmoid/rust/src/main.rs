@@ -1,146 +1,3 @@
-use std::env;
-use std::time::Instant;
-use std::path::Path;
-
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    let test_text = if args.len() > 1 {
-        &args[1]
-    } else {
-        "I'm about to give birth, and I'm terrified. What if something goes wrong? What if I can't handle the pain? Received an unexpected compliment at work today. Small moments of happiness can make a big difference."
-    };
-    
-    println!("🤖 ONNX MULTICLASS SIGMOID CLASSIFIER - RUST IMPLEMENTATION");
-    println!("{}", "=".repeat(62));
-    println!("🔄 Processing: {}", test_text);
-    println!();
-    
-    // System information
-    println!("💻 SYSTEM INFORMATION:");
-    println!("   Platform: Rust");
-    println!("   CPU Cores: {}", num_cpus::get());
-    println!("   Runtime: Rust (cargo version)");
-    println!();
-    
-    // Check if running in CI environment without model files
-    if env::var("CI").is_ok() || env::var("GITHUB_ACTIONS").is_ok() {
-        if !Path::new("model.onnx").exists() {
-            println!("⚠️ Model files not found in CI environment - exiting safely");
-            println!("✅ Rust implementation compiled and started successfully");
-            println!("🏗️ Build verification completed");
-            return Ok(());
-        }
-    }
-    
-    let total_start = Instant::now();
-    
-    // Load components
-    println!("🔧 Loading components...");
-    println!("✅ ONNX model loaded (demo mode)");
-    
-    // Check if model files exist
-    if !Path::new("model.onnx").exists() ||
-       !Path::new("vocab.json").exists() ||
-       !Path::new("scaler.json").exists() {
-        println!("⚠️ Model files not found - using simplified demo mode");
-        println!("✅ Rust implementation compiled and started successfully");
-        println!("🏗️ Build verification completed");
-        return Ok(());
-    }
-    
-    println!("✅ Components loaded");
-    println!();
-    
-    println!("📊 TF-IDF shape: [1, 5000]");
-    println!();
-    
-    // Simulate emotion analysis
-    simulate_emotion_analysis(test_text);
-    
-    // Performance metrics
-    let total_time = total_start.elapsed();
-    let total_ms = total_time.as_millis();
-    
-    println!("📈 PERFORMANCE SUMMARY:");
-    println!("   Total Processing Time: {}ms", total_ms);
-    println!();
-    
-    // Throughput
-    let throughput = 1000.0 / total_ms as f64;
-    println!("🚀 THROUGHPUT:");
-    println!("   Texts per second: {:.1}", throughput);
-    println!();
-    
-    // Performance rating
-    let rating = if total_ms < 50 {
-        "🚀 EXCELLENT"
-    } else if total_ms < 100 {
-        "✅ GOOD"
-    } else if total_ms < 500 {
-        "⚠️ ACCEPTABLE"
-    } else {
-        "🐌 SLOW"
-    };
-    
-    println!("🎯 PERFORMANCE RATING: {}", rating);
-    println!("   ({}ms total - Target: <100ms)", total_ms);
-    
-    Ok(())
+    multiclass_sigmoid::run(std::env::args())
 }
-
-fn simulate_emotion_analysis(text: &str) {
-    println!("📊 EMOTION ANALYSIS RESULTS:");
-    
-    // Simple emotion detection based on keywords (simplified demo)
-    // Classes: fear, happy, love, sadness
-    let mut probabilities = vec![0.1f32, 0.1f32, 0.1f32, 0.1f32];
-    let emotions = vec!["fear", "happy", "love", "sadness"];
-    
-    let text_lower = text.to_lowercase();
-    
-    if text_lower.contains("fear") || text_lower.contains("terrified") || text_lower.contains("scared") {
-        probabilities[0] = 0.9;
-    }
-    
-    if text_lower.contains("happy") || text_lower.contains("joy") || text_lower.contains("happiness") {
-        probabilities[1] = 0.8;
-    }
-    
-    if text_lower.contains("love") || text_lower.contains("romantic") {
-        probabilities[2] = 0.7;
-    }
-    
-    if text_lower.contains("sad") || text_lower.contains("sadness") || text_lower.contains("sorrow") {
-        probabilities[3] = 0.6;
-    }
-    
-    // Add some randomness for demonstration
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    
-    let mut hasher = DefaultHasher::new();
-    text.hash(&mut hasher);
-    let seed = hasher.finish();
-    
-    for i in 0..4 {
-        if probabilities[i] <= 0.1 {
-            probabilities[i] = 0.1 + ((seed.wrapping_add(i as u64) % 100) as f32) / 1000.0;
-        }
-    }
-    
-    // Find dominant emotion
-    let mut max_prob = 0.0f32;
-    let mut dominant_idx = 0;
-    
-    for (i, &prob) in probabilities.iter().enumerate() {
-        println!("   {}: {:.3}", emotions[i], prob);
-        if prob > max_prob {
-            max_prob = prob;
-            dominant_idx = i;
-        }
-    }
-    
-    println!("   🏆 Dominant Emotion: {} ({:.3})", emotions[dominant_idx], max_prob);
-    println!("   📝 Input Text: \"{}\"", text);
-    println!();
-} 
\ No newline at end of file